@@ -0,0 +1,102 @@
+//! One-off scheduled announcements
+//!
+//! Single events (e.g. a tech talk in the lab) that show through the same
+//! Pending/Active flow as checks and expire once shown. Shown announcements
+//! are remembered in the state folder so a restart doesn't show them again.
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// A message shown once at a set date and time
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    /// When to show it (e.g. "2026-10-20T16:00:00")
+    pub at: NaiveDateTime,
+    /// Text shown on screen
+    pub message: String,
+
+    /// Sound to play when it goes off; a short chime is used when unset.
+    ///
+    /// If relative, it is resolved relative to the executable's directory.
+    #[serde(default)]
+    pub sound: Option<PathBuf>,
+}
+
+impl Announcement {
+    /// Returns the moment this announcement goes off in local time
+    pub fn local_time(&self) -> Option<DateTime<Local>> {
+        self.at.and_local_timezone(Local).earliest()
+    }
+}
+
+/// Announcements already shown, saved to a file so they stay shown across restarts
+#[derive(Debug, Default)]
+pub struct ShownAnnouncements {
+    /// File the list is kept in; `None` keeps it in memory only
+    path: Option<PathBuf>,
+    shown: Vec<ShownAnnouncement>,
+}
+
+/// An announcement identified by its time and message
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct ShownAnnouncement {
+    at: NaiveDateTime,
+    message: String,
+}
+
+impl ShownAnnouncements {
+    /// Reads the list from `path`, starting empty if it doesn't exist or can't be read
+    pub fn load(path: PathBuf) -> Self {
+        let shown = match fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                tracing::warn!("Invalid shown announcements in {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                tracing::warn!("Can't read {}: {e}", path.display());
+                Vec::new()
+            }
+        };
+        Self {
+            path: Some(path),
+            shown,
+        }
+    }
+
+    /// Returns whether `announcement` has been shown before
+    pub fn contains(&self, announcement: &Announcement) -> bool {
+        self.shown
+            .iter()
+            .any(|s| s.at == announcement.at && s.message == announcement.message)
+    }
+
+    /// Remembers `announcement` as shown and saves the list.
+    ///
+    /// Entries from before `forget_before` are dropped, as those announcements
+    /// are too old to show anyway.
+    pub fn record(&mut self, announcement: &Announcement, forget_before: NaiveDateTime) {
+        self.shown.retain(|s| s.at >= forget_before);
+        self.shown.push(ShownAnnouncement {
+            at: announcement.at,
+            message: announcement.message.clone(),
+        });
+
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| {
+                let json = serde_json::to_string_pretty(&self.shown).map_err(io::Error::other)?;
+                fs::write(path, json)
+            });
+        if let Err(e) = saved {
+            tracing::warn!("Can't save shown announcements to {}: {e}", path.display());
+        }
+    }
+}
@@ -2,248 +2,1374 @@
 //!
 //! Manages the reminder state machine and renders the user interface.
 
-use crate::config::Config;
-use crate::scheduler::{CheckType, check_time, minutes_until_next_check};
-use crate::sound::Audio;
-use chrono::{Local, Timelike};
-use eframe::egui::{CentralPanel, Context};
+use crate::announcement::{Announcement, ShownAnnouncements};
+use crate::clock::Clock;
+use crate::config::{Config, FlashAlerts, MissedCheckPolicy, PreAlertKind};
+use crate::keymap::{KeyAction, Keymap};
+use crate::kiosk::KioskLock;
+use crate::logging;
+use crate::message_board::{BoardMessage, MessageBoard};
+use crate::notifications::{NotificationAction, Notifier};
+use crate::qr;
+use crate::scheduler::{CheckType, Schedule};
+use crate::sound::{AudioOutput, AudioStatus, PlayError, TrackInfo};
+use crate::theme::Theme;
+use crate::tray::{TrayCommand, TrayIcon, TrayState};
+use chrono::{DateTime, Local, Timelike};
+use eframe::egui::{
+    Align, CentralPanel, Context, Layout, TopBottomPanel, ViewportBuilder, ViewportCommand,
+    ViewportId, WindowLevel,
+};
 use eframe::{Frame, egui};
 use egui::{Button, Color32, RichText, vec2};
-use rand::seq::IndexedRandom;
+use rand::seq::SliceRandom;
 use rodio::Sink;
-use std::time::Duration;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Window size the layout is designed for; larger windows zoom in, smaller ones zoom out
+const DESIGN_SIZE: egui::Vec2 = vec2(1920.0, 1080.0);
+
+/// What a reminder is about
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Reminder {
+    /// A recurring check from the schedule
+    Check(CheckType),
+    /// A one-off announcement with its own message
+    Announcement(Announcement),
+}
+
+impl fmt::Display for Reminder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reminder::Check(check) => write!(f, "{check}"),
+            Reminder::Announcement(announcement) => write!(f, "{}", announcement.message),
+        }
+    }
+}
 
 /// Current state of the reminder system
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ReminderState {
     /// No active or upcoming reminders
     Idle,
-    /// Reminder coming in the next 5 minutes
-    Pending(CheckType),
+    /// Reminder coming within the pending window
+    Pending(Reminder),
     /// Reminder is currently active, waiting for user action
-    Active(CheckType),
+    Active(Reminder),
+}
+
+impl fmt::Display for ReminderState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReminderState::Idle => write!(f, "Idle"),
+            ReminderState::Pending(reminder) => write!(f, "Pending ({reminder})"),
+            ReminderState::Active(reminder) => write!(f, "Active ({reminder})"),
+        }
+    }
+}
+
+/// An Active reminder put off for a few minutes
+struct Snoozed {
+    reminder: Reminder,
+    /// When it goes off again
+    until: DateTime<Local>,
+    /// Trigger time of the check, if it came from the schedule
+    trigger: Option<DateTime<Local>>,
+}
+
+/// A form link the browser couldn't be opened for, offered as a QR code instead
+struct FormFallback {
+    url: String,
+    error: String,
 }
 
 /// Main application struct managing GUI and state
 pub struct MentorApp {
     config: Config,
+    schedule: Schedule,
+    /// Announcements not yet shown, soonest first
+    announcements: Vec<Announcement>,
+    /// Announcements shown in this or an earlier run
+    shown_announcements: ShownAnnouncements,
+    message_board: MessageBoard,
+    /// Board message shown this frame in every window; `None` is the greeting
+    board_message: Option<BoardMessage>,
     state: ReminderState,
     last_state: ReminderState,
-    trigger_consumed: bool,
-    audio: Option<Audio>,
+    /// Trigger minute that was last handled, so each trigger goes off once
+    consumed_trigger: Option<DateTime<Local>>,
+    /// Checks that came due while another reminder was Active, oldest first
+    queued_checks: Vec<(DateTime<Local>, CheckType)>,
+    /// Time of the previous `update_state` run, used to notice skipped triggers
+    last_evaluated: Option<DateTime<Local>>,
+    /// Trigger time of the active check when it was activated late
+    late_since: Option<DateTime<Local>>,
+    /// Trigger time of the active check, if it came from the schedule
+    active_trigger: Option<DateTime<Local>>,
+    /// Trigger times of checks acknowledged today, for the timeline
+    completed_checks: Vec<DateTime<Local>>,
+    audio: AudioOutput,
     current_sink: Option<Sink>,
+    /// Songs still to play for the active reminder, next one last
+    song_queue: Vec<PathBuf>,
+    now_playing: Option<TrackInfo>,
     after_hours: bool,
+    /// Check time the fired pre-alerts belong to, and which ones have fired
+    pre_alert_target: Option<DateTime<Local>>,
+    pre_alerts_fired: Vec<usize>,
+    /// Toast message on screen and when it was shown
+    toast: Option<(String, Instant)>,
+    theme: Theme,
+    /// Window was brought forward for the current Active reminder
+    window_raised: bool,
+    /// Unlock dialog and window guards, when running in kiosk mode
+    kiosk: Option<KioskLock>,
+    /// Public display window is shown; cleared when someone closes it
+    public_display_open: bool,
+    snoozed: Option<Snoozed>,
+    notifier: Notifier,
+    tray: TrayIcon,
+    keymap: Keymap,
+    help_open: bool,
+    /// Time source; only shifted or sped up from the debug panel
+    clock: Clock,
+    /// Notification button pressed while a reminder was Pending, applied once it activates
+    deferred_action: Option<(Reminder, NotificationAction)>,
+    /// Shown in a dialog after "Open Form" failed to launch a browser
+    form_fallback: Option<FormFallback>,
 }
 
 impl MentorApp {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, ctx: &Context) -> Self {
         Self {
-            config,
             state: ReminderState::Idle,
             last_state: ReminderState::Idle,
-            trigger_consumed: false,
-            audio: None,
+            consumed_trigger: None,
+            queued_checks: Vec::new(),
+            last_evaluated: None,
+            late_since: None,
+            active_trigger: None,
+            completed_checks: Vec::new(),
+            audio: AudioOutput::new(config.audio_device.clone()),
             current_sink: None,
+            song_queue: Vec::new(),
+            now_playing: None,
             after_hours: false,
+            pre_alert_target: None,
+            pre_alerts_fired: Vec::new(),
+            toast: None,
+            window_raised: false,
+            kiosk: config.kiosk.enabled.then(|| KioskLock::new(&config.kiosk)),
+            public_display_open: config.public_display.enabled,
+            snoozed: None,
+            notifier: Notifier::new(config.notifications, ctx),
+            tray: TrayIcon::new(config.tray_icon, ctx),
+            keymap: Keymap::new(&config.keys, config.debug),
+            help_open: false,
+            clock: Clock::default(),
+            deferred_action: None,
+            form_fallback: None,
+            schedule: config.schedule(),
+            theme: Theme::from_config(&config.theme, &config.accessibility),
+            message_board: MessageBoard::new(config.messages.clone(), config.messages_file.clone()),
+            board_message: None,
+            announcements: {
+                let mut announcements = config.announcements.clone();
+                announcements.sort_by_key(|a| a.at);
+                announcements
+            },
+            shown_announcements: ShownAnnouncements::load(
+                logging::state_dir().join("shown_announcements.json"),
+            ),
+            config,
         }
     }
 
     /// Updates the reminder state based on current time and plays audio when transitioning to Active
     fn update_state(&mut self) {
-        const PENDING_WINDOW_MINUTES: i64 = 5;
+        let now = self.clock.now();
+        let current_trigger = self.schedule.check_time(now);
+
+        // On startup, look back far enough to catch a check we just missed.
+        let max_late = chrono::Duration::minutes(self.config.missed_checks.max_late_minutes);
+        let previous = self.last_evaluated.replace(now).unwrap_or(now - max_late);
+
+        // Forget acknowledgements from previous days.
+        self.completed_checks
+            .retain(|at| at.date_naive() == now.date_naive());
 
-        let now = Local::now();
-        let current_trigger = check_time();
+        // Rebuild a lost audio stream; resume the interrupted song if a reminder is playing.
+        if self.audio.poll() && matches!(self.state, ReminderState::Active(_)) {
+            if let Some(track) = self.now_playing.take() {
+                self.song_queue.push(track.path);
+            }
+            self.play_next();
+        }
+
+        self.handle_notification_actions();
 
-        // After hours
-        if now.hour() < 10 || now.hour() >= 18 {
+        // After hours; snoozes and deferred notification buttons don't carry over to the next day.
+        if !self.schedule.is_open(now) {
             self.state = ReminderState::Idle;
             self.after_hours = true;
+            self.snoozed = None;
+            self.deferred_action = None;
+            self.queued_checks.clear();
             return;
         } else {
             self.after_hours = false;
         }
 
-        // Reset consumption once we're no longer on a trigger minute.
-        if current_trigger.is_none() {
-            self.trigger_consumed = false;
+        // Pending reminder shortly before the trigger moment.
+        if let Some((next_at, next)) = self.next_reminder(now) {
+            let seconds_until = (next_at - now).num_seconds();
+            let in_pending_window = (0..self.config.pending_minutes * 60).contains(&seconds_until);
+            if in_pending_window
+                && matches!(self.state, ReminderState::Idle | ReminderState::Pending(_))
+            {
+                self.state = ReminderState::Pending(next);
+            }
+        }
+
+        if let Some((next_at, next_check)) = self.schedule.next_check(now) {
+            let seconds_until = (next_at - now).num_seconds();
+            self.fire_pre_alerts(next_check, next_at, seconds_until);
         }
 
-        // Pending check shortly before the trigger moment.
-        let (next_check, minutes_until) = minutes_until_next_check(now);
-        let in_pending_window = (1..=PENDING_WINDOW_MINUTES).contains(&minutes_until);
-        if in_pending_window
-            && matches!(self.state, ReminderState::Idle | ReminderState::Pending(_))
+        // Triggers skipped over since the last run, whose minute has already passed.
+        self.handle_missed_checks(previous, now);
+
+        // Moment reminder goes off, once per trigger minute.
+        let trigger_minute = now.with_second(0).and_then(|t| t.with_nanosecond(0));
+        if let Some(check) = current_trigger
+            && self.consumed_trigger != trigger_minute
         {
-            self.state = ReminderState::Pending(next_check);
+            self.consumed_trigger = trigger_minute;
+            // A snoozed reminder that hasn't come back yet is replaced by the new check.
+            self.snoozed = None;
+
+            if let ReminderState::Active(active) = &self.state {
+                tracing::info!("{check} is due while {active} is active; queued");
+                self.queued_checks
+                    .extend(trigger_minute.map(|at| (at, check)));
+            } else {
+                self.state = ReminderState::Active(Reminder::Check(check));
+                self.late_since = None;
+                self.active_trigger = trigger_minute;
+            }
         }
 
-        // Moment reminder goes off.
-        if let Some(check) = current_trigger {
-            let is_already_active = matches!(self.state, ReminderState::Active(_));
-            if !is_already_active && !self.trigger_consumed {
-                self.state = ReminderState::Active(check);
-                self.trigger_consumed = true;
+        // Queued checks go off once the active reminder is dealt with, unless too old by then.
+        self.queued_checks.retain(|(at, check)| {
+            let fresh = now - *at <= max_late;
+            if !fresh {
+                tracing::warn!("Missed {check} at {}", at.format("%H:%M"));
             }
+            fresh
+        });
+        if !matches!(self.state, ReminderState::Active(_)) && !self.queued_checks.is_empty() {
+            let (at, check) = self.queued_checks.remove(0);
+            self.state = ReminderState::Active(Reminder::Check(check));
+            self.late_since = Some(at);
+            self.active_trigger = Some(at);
         }
 
+        // Snoozed reminders come back once their time is up and nothing else is active.
+        if !matches!(self.state, ReminderState::Active(_))
+            && let Some(snoozed) = self.snoozed.take_if(|s| s.until <= now)
+        {
+            self.state = ReminderState::Active(snoozed.reminder);
+            self.late_since = None;
+            self.active_trigger = snoozed.trigger;
+        }
+
+        // Announcements go off once no other reminder is active.
+        self.handle_announcements(now);
+
         // React to state transitions.
         if self.last_state != self.state {
-            if matches!(self.state, ReminderState::Active(_)) {
-                self.audio = self.audio.take().or_else(Audio::new);
-
-                if let (Some(audio), Some(path)) = (
-                    self.audio.as_ref(),
-                    self.config.songs.choose(&mut rand::rng()).cloned(),
-                ) {
-                    self.current_sink = audio.play_file(path);
+            tracing::info!(from = %self.last_state, to = %self.state, "Reminder state changed");
+
+            if let ReminderState::Active(reminder) = self.state.clone() {
+                // A button pressed for a different reminder no longer applies.
+                match self.deferred_action.take().filter(|(r, _)| *r == reminder) {
+                    Some((_, NotificationAction::Checked)) => self.acknowledge(),
+                    Some((_, NotificationAction::Snooze)) => self.snooze(),
+                    None => self.start_music(now, &reminder),
                 }
             }
 
-            self.last_state = self.state;
+            self.notify_state(now);
+            self.last_state = self.state.clone();
         }
     }
 
-    /// Returns a dynamic, breathing RGB effect background
-    fn background_color(&self, t: f32) -> Color32 {
-        let speed: f32 = 0.2;
-        let phase: f32 = t * speed;
+    /// Applies buttons pressed on desktop notifications.
+    ///
+    /// Buttons on an upcoming reminder's notification take effect when it goes off.
+    fn handle_notification_actions(&mut self) {
+        while let Some(action) = self.notifier.poll() {
+            match (&self.state, action) {
+                (ReminderState::Active(_), NotificationAction::Checked) => self.acknowledge(),
+                (ReminderState::Active(_), NotificationAction::Snooze) => self.snooze(),
+                (ReminderState::Pending(reminder), action) => {
+                    self.deferred_action = Some((reminder.clone(), action));
+                }
+                (ReminderState::Idle, _) => {}
+            }
+        }
+    }
 
-        let r = phase.sin() * 0.5 + 0.5;
-        let g = (phase + 2.0).sin() * 0.5 + 0.5;
-        let b = (phase + 4.0).sin() * 0.5 + 0.5;
+    /// Shows a desktop notification for the new state, or closes it when Idle
+    fn notify_state(&mut self, now: DateTime<Local>) {
+        match &self.state {
+            ReminderState::Idle => self.notifier.close(),
+            ReminderState::Pending(reminder) => {
+                let summary = match reminder {
+                    Reminder::Check(_) => "Upcoming check",
+                    Reminder::Announcement(_) => "Upcoming announcement",
+                };
+                let body = match self.next_reminder(now) {
+                    Some((at, _)) => format!("{reminder} at {}", at.format("%H:%M")),
+                    None => reminder.to_string(),
+                };
+                self.notifier.show(summary, &body, false);
+            }
+            ReminderState::Active(reminder) => {
+                let summary = match reminder {
+                    Reminder::Check(_) => "Time to check in!",
+                    Reminder::Announcement(_) => "Announcement",
+                };
+                self.notifier.show(summary, &reminder.to_string(), true);
+            }
+        }
+    }
 
-        // soften it
-        let base = 30.0;
-        let range = 40.0;
+    /// Fires each configured pre-alert once, during its minute before the next check
+    fn fire_pre_alerts(&mut self, check: CheckType, next_at: DateTime<Local>, seconds_until: i64) {
+        if self.pre_alert_target != Some(next_at) {
+            self.pre_alert_target = Some(next_at);
+            self.pre_alerts_fired.clear();
+        }
+
+        for (i, alert) in self.config.pre_alerts.iter().enumerate() {
+            let window = (alert.minutes_before - 1) * 60..alert.minutes_before * 60;
+            if !window.contains(&seconds_until) || self.pre_alerts_fired.contains(&i) {
+                continue;
+            }
+            self.pre_alerts_fired.push(i);
 
-        Color32::from_rgb(
-            (base + r * range) as u8,
-            (base + g * range) as u8,
-            (base + b * range) as u8,
+            match alert.kind {
+                PreAlertKind::Chime => {
+                    if let Some(audio) = self.audio.get() {
+                        audio.play_chime(alert.sound.as_deref());
+                    }
+                }
+                PreAlertKind::Toast => {
+                    let message = alert
+                        .message
+                        .clone()
+                        .unwrap_or_else(|| format!("{} in {} min", check, alert.minutes_before));
+                    self.toast = Some((message, Instant::now()));
+                }
+            }
+        }
+    }
+
+    /// Activates or logs checks whose trigger minute passed between two runs
+    fn handle_missed_checks(&mut self, previous: DateTime<Local>, now: DateTime<Local>) {
+        let one_minute = chrono::Duration::minutes(1);
+        let max_late = chrono::Duration::minutes(self.config.missed_checks.max_late_minutes);
+
+        let missed: Vec<_> = self
+            .schedule
+            .triggers_between(previous, now)
+            .into_iter()
+            .filter(|(at, _)| *at + one_minute <= now)
+            .collect();
+
+        let count = missed.len();
+        for (i, (at, check)) in missed.into_iter().enumerate() {
+            let is_latest = i + 1 == count;
+            let activate = is_latest
+                && self.config.missed_checks.policy == MissedCheckPolicy::Activate
+                && now - at <= max_late
+                && !matches!(self.state, ReminderState::Active(_));
+
+            if activate {
+                tracing::warn!(
+                    "Missed {check} at {}; activating it late",
+                    at.format("%H:%M")
+                );
+                self.state = ReminderState::Active(Reminder::Check(check));
+                self.late_since = Some(at);
+                self.active_trigger = Some(at);
+            } else {
+                tracing::warn!("Missed {check} at {}", at.format("%H:%M"));
+            }
+        }
+    }
+
+    /// Returns the soonest upcoming check or announcement after `now`
+    fn next_reminder(&self, now: DateTime<Local>) -> Option<(DateTime<Local>, Reminder)> {
+        let next_check = self
+            .schedule
+            .next_check(now)
+            .map(|(at, check)| (at, Reminder::Check(check)));
+
+        let next_announcement = self
+            .announcements
+            .iter()
+            .filter_map(|a| Some((a.local_time()?, a)))
+            .filter(|(at, _)| *at > now)
+            .min_by_key(|(at, _)| *at)
+            .map(|(at, a)| (at, Reminder::Announcement(a.clone())));
+
+        match (next_check, next_announcement) {
+            (Some(check), Some(announcement)) if announcement.0 < check.0 => Some(announcement),
+            (Some(check), _) => Some(check),
+            (None, announcement) => announcement,
+        }
+    }
+
+    /// Shows the first due announcement unless a reminder is already active.
+    ///
+    /// Shown announcements are removed, including ones shown before a restart.
+    /// Ones too old to show late are logged and dropped.
+    fn handle_announcements(&mut self, now: DateTime<Local>) {
+        let max_late = chrono::Duration::minutes(self.config.missed_checks.max_late_minutes);
+
+        let shown = &self.shown_announcements;
+        self.announcements.retain(|a| !shown.contains(a));
+        self.announcements.retain(|a| match a.local_time() {
+            Some(at) if now - at > max_late => {
+                tracing::warn!(
+                    "Missed announcement \"{}\" at {}",
+                    a.message,
+                    at.format("%H:%M")
+                );
+                false
+            }
+            Some(_) => true,
+            None => false,
+        });
+
+        if matches!(self.state, ReminderState::Active(_)) {
+            return;
+        }
+
+        if let Some(i) = self
+            .announcements
+            .iter()
+            .position(|a| a.local_time().is_some_and(|at| at <= now))
+        {
+            let announcement = self.announcements.remove(i);
+            self.shown_announcements
+                .record(&announcement, (now - max_late).naive_local());
+            self.state = ReminderState::Active(Reminder::Announcement(announcement));
+            self.late_since = None;
+            self.active_trigger = None;
+        }
+    }
+
+    /// Acknowledges the active reminder: stops the music and returns to Idle
+    fn acknowledge(&mut self) {
+        self.stop_music();
+        self.state = ReminderState::Idle;
+        self.late_since = None;
+        if let Some(at) = self.active_trigger.take() {
+            self.completed_checks.push(at);
+        }
+    }
+
+    /// Silences the active reminder and brings it back after `snooze_minutes`
+    fn snooze(&mut self) {
+        let ReminderState::Active(reminder) = self.state.clone() else {
+            return;
+        };
+
+        self.stop_music();
+        self.snoozed = Some(Snoozed {
+            reminder,
+            until: self.clock.now() + chrono::Duration::minutes(self.config.snooze_minutes),
+            trigger: self.active_trigger,
+        });
+        self.state = ReminderState::Idle;
+        self.late_since = None;
+    }
+
+    /// Returns the active check's form link with its placeholders filled in
+    fn form_link(&self, now: DateTime<Local>) -> Option<String> {
+        let ReminderState::Active(Reminder::Check(check)) = &self.state else {
+            return None;
+        };
+        let url = check.form_url.as_ref()?;
+        Some(
+            self.config
+                .form_link(url, check, self.active_trigger.unwrap_or(now)),
         )
     }
 
-    fn invert_color(color: Color32) -> Color32 {
-        let [r, g, b, a] = color.to_array();
-        Color32::from_rgba_unmultiplied(255 - r, 255 - g, 255 - b, a)
+    /// Opens the active check's form in the browser, if it has one
+    fn open_form(&mut self) {
+        if let Some(url) = self.form_link(self.clock.now())
+            && let Err(e) = self.config.open_in_browser(&url)
+        {
+            tracing::error!(url, "Failed to open the form in a browser: {e}");
+            self.form_fallback = Some(FormFallback {
+                url,
+                error: e.to_string(),
+            });
+        }
+    }
+
+    /// Shows the form link as a QR code and copyable text after the browser failed to open
+    fn show_form_fallback(&mut self, ctx: &Context) {
+        let Some(fallback) = &self.form_fallback else {
+            return;
+        };
+
+        let modal = egui::Modal::new(egui::Id::new("form_fallback")).show(ctx, |ui| {
+            ui.set_width(360.0);
+            ui.heading("Couldn't open the browser");
+            ui.colored_label(ui.visuals().error_fg_color, &fallback.error);
+            ui.add_space(10.0);
+
+            ui.label("Scan the code or copy the link to fill out the form:");
+            ui.vertical_centered(|ui| qr::qr_code(ui, &fallback.url, 240.0));
+            ui.add_space(6.0);
+            qr::copyable_link(ui, &fallback.url);
+
+            ui.add_space(10.0);
+            ui.button("Close").clicked()
+        });
+
+        if modal.inner || modal.should_close() {
+            self.form_fallback = None;
+        }
+    }
+
+    /// Makes a check go off now, for testing
+    fn debug_trigger(&mut self, check: CheckType) {
+        self.state = ReminderState::Active(Reminder::Check(check));
+        self.consumed_trigger = self
+            .clock
+            .now()
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0));
+        self.late_since = None;
+        self.active_trigger = None;
+    }
+
+    /// Draws the debug panel: internal state, clock controls and forced transitions
+    fn draw_debug_panel(&mut self, ctx: &Context, now: DateTime<Local>) {
+        let next_check = self.schedule.next_check(now);
+
+        egui::Window::new("Debug")
+            .default_pos([20.0, 80.0])
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("debug_state")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        let rows = [
+                            ("time", now.format("%a %Y-%m-%d %H:%M:%S").to_string()),
+                            ("speed", format!("{}x", self.clock.speed())),
+                            ("state", format!("{:?}", self.state)),
+                            ("last_state", format!("{:?}", self.last_state)),
+                            (
+                                "consumed_trigger",
+                                format!(
+                                    "{:?}",
+                                    self.consumed_trigger.map(|t| t.format("%H:%M").to_string())
+                                ),
+                            ),
+                            ("queued_checks", self.queued_checks.len().to_string()),
+                            ("after_hours", self.after_hours.to_string()),
+                            (
+                                "next check",
+                                next_check
+                                    .as_ref()
+                                    .map(|(at, check)| {
+                                        format!("{check} at {}", at.format("%a %H:%M"))
+                                    })
+                                    .unwrap_or_else(|| "none".to_string()),
+                            ),
+                            (
+                                "late_since",
+                                format!(
+                                    "{:?}",
+                                    self.late_since.map(|t| t.format("%H:%M").to_string())
+                                ),
+                            ),
+                            (
+                                "snoozed until",
+                                format!(
+                                    "{:?}",
+                                    self.snoozed
+                                        .as_ref()
+                                        .map(|s| s.until.format("%H:%M:%S").to_string())
+                                ),
+                            ),
+                        ];
+                        for (name, value) in rows {
+                            ui.label(name);
+                            ui.monospace(value);
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.label("Time");
+                ui.horizontal(|ui| {
+                    let shifts = [
+                        ("-1h", -60),
+                        ("-5m", -5),
+                        ("+1m", 1),
+                        ("+5m", 5),
+                        ("+1h", 60),
+                    ];
+                    for (label, minutes) in shifts {
+                        if ui.button(label).clicked() {
+                            self.clock.shift(chrono::Duration::minutes(minutes));
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    for speed in [1.0, 10.0, 60.0, 600.0] {
+                        if ui
+                            .selectable_label(self.clock.speed() == speed, format!("{speed}x"))
+                            .clicked()
+                        {
+                            self.clock.set_speed(speed);
+                        }
+                    }
+                    if ui.button("Reset").clicked() {
+                        self.clock.reset();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let next_at = next_check.as_ref().map(|(at, _)| *at);
+                    let pending_window = chrono::Duration::minutes(self.config.pending_minutes);
+                    let seconds = chrono::Duration::seconds;
+                    let jumps = [
+                        (
+                            "Pending",
+                            next_at.map(|at| at - pending_window + seconds(5)),
+                        ),
+                        ("Check", next_at.map(|at| at - seconds(5))),
+                        (
+                            "Closing",
+                            self.schedule.closing_time(now).map(|at| at - seconds(5)),
+                        ),
+                    ];
+                    ui.label("Jump to");
+                    for (label, target) in jumps {
+                        if ui
+                            .add_enabled(target.is_some(), egui::Button::new(label))
+                            .clicked()
+                            && let Some(target) = target
+                        {
+                            self.clock.set(target);
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.label("Force");
+                ui.horizontal_wrapped(|ui| {
+                    if ui.button("Idle").clicked() {
+                        self.stop_music();
+                        self.state = ReminderState::Idle;
+                        self.late_since = None;
+                    }
+                    if ui.button("Snooze").clicked() {
+                        self.snooze();
+                    }
+                    for check in self.schedule.checks().to_vec() {
+                        if ui.button(format!("Active: {check}")).clicked() {
+                            self.debug_trigger(check);
+                        }
+                    }
+                });
+            });
+    }
+
+    /// Runs the actions whose keyboard shortcuts were pressed
+    fn handle_shortcuts(&mut self, ctx: &Context) {
+        let active = matches!(self.state, ReminderState::Active(_));
+        // Reminder shortcuts work on a locked kiosk; forcing a check needs it unlocked.
+        let kiosk_locked = self.kiosk.as_ref().is_some_and(KioskLock::is_locked);
+
+        for action in self.keymap.pressed(ctx) {
+            match action {
+                KeyAction::Acknowledge if active => self.acknowledge(),
+                KeyAction::Acknowledge => {}
+                KeyAction::OpenForm => self.open_form(),
+                KeyAction::Pause => self.toggle_pause(),
+                KeyAction::Snooze => self.snooze(),
+                KeyAction::SkipTrack if !self.song_queue.is_empty() => self.play_next(),
+                KeyAction::SkipTrack => {}
+                KeyAction::DebugTrigger if kiosk_locked => {}
+                KeyAction::DebugTrigger => {
+                    if let Some(check) = self.schedule.checks().first() {
+                        self.debug_trigger(check.clone());
+                    }
+                }
+                KeyAction::Help => self.help_open = !self.help_open,
+            }
+        }
+    }
+
+    /// Pauses the reminder's music, or resumes it if paused
+    fn toggle_pause(&mut self) {
+        if let Some(sink) = self.current_sink.as_ref() {
+            if sink.is_paused() {
+                sink.play();
+            } else {
+                sink.pause();
+            }
+        }
+    }
+
+    /// Starts the sound for a reminder going off.
+    ///
+    /// Checks shuffle their own sounds if they have any, otherwise the
+    /// mentor's or shared songs. Announcements play their sound, or a chime.
+    fn start_music(&mut self, now: DateTime<Local>, reminder: &Reminder) {
+        self.song_queue = match reminder {
+            Reminder::Check(check) if !check.songs.is_empty() => check.songs.clone(),
+            Reminder::Check(_) => self.config.songs_for(now).to_vec(),
+            Reminder::Announcement(announcement) => match &announcement.sound {
+                Some(sound) => vec![sound.clone()],
+                None => {
+                    if let Some(audio) = self.audio.get() {
+                        audio.play_chime(None);
+                    }
+                    Vec::new()
+                }
+            },
+        };
+        self.song_queue.shuffle(&mut rand::rng());
+        self.play_next();
+    }
+
+    /// Stops the current song and plays the next playable one in the queue
+    fn play_next(&mut self) {
+        self.stop_music();
+
+        let mut rebuilt = false;
+        while let Some(path) = self.song_queue.pop() {
+            let Some(audio) = self.audio.get() else {
+                self.song_queue.push(path);
+                return;
+            };
+
+            match audio.play_file(path.clone()) {
+                Ok(sink) => {
+                    tracing::info!(song = %path.display(), "Playing song");
+                    self.current_sink = Some(sink);
+                    self.now_playing = Some(TrackInfo::read(&path));
+                    return;
+                }
+                // Skip files that can't be read and try the next song.
+                Err(PlayError::File) => {}
+                // Rebuild the stream and retry the same song once; after that the
+                // song waits until `AudioOutput::poll` recovers the stream.
+                Err(PlayError::Output) => {
+                    self.audio.mark_broken();
+                    self.song_queue.push(path);
+                    if rebuilt {
+                        return;
+                    }
+                    rebuilt = true;
+                }
+            }
+        }
+    }
+
+    /// Stops and releases the current song, if any
+    fn stop_music(&mut self) {
+        if let Some(sink) = self.current_sink.take() {
+            sink.stop();
+            sink.detach();
+        }
+        self.now_playing = None;
+    }
+
+    /// Formats a duration as m:ss
+    fn format_duration(duration: Duration) -> String {
+        let secs = duration.as_secs();
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+
+    /// Draws a strip of today's recent checks, the next few checks and closing time
+    fn draw_timeline(&self, ui: &mut egui::Ui, now: DateTime<Local>, theme: &Theme) {
+        const PAST_SHOWN: usize = 2;
+        const UPCOMING_SHOWN: usize = 4;
+
+        let start_of_day = now
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|t| t.and_local_timezone(Local).earliest())
+            .unwrap_or(now);
+        let past = self.schedule.triggers_between(start_of_day, now);
+        let past = &past[past.len().saturating_sub(PAST_SHOWN)..];
+        let upcoming: Vec<_> = self.schedule.upcoming(now).take(UPCOMING_SHOWN).collect();
+
+        let entries = past
+            .iter()
+            .map(|(at, check)| {
+                let (mark, color) = if self.completed_checks.contains(at) {
+                    ("✔", theme.readable(theme.palette.success))
+                } else if self.active_trigger == Some(*at) {
+                    ("⚠", theme.palette.warning)
+                } else {
+                    ("✘", theme.palette.danger)
+                };
+                (format!("{mark} {} {check}", at.format("%H:%M")), color)
+            })
+            .chain(upcoming.iter().map(|(at, check)| {
+                (
+                    format!("{} {check}", at.format("%H:%M")),
+                    theme.palette.muted,
+                )
+            }))
+            .chain(self.schedule.closing_time(now).map(|at| {
+                (
+                    format!("{} Lab closes", at.format("%H:%M")),
+                    theme.palette.after_hours,
+                )
+            }));
+
+        // One label built from colored segments so the strip stays centered.
+        let mut job = egui::text::LayoutJob::default();
+        for (i, (text, color)) in entries.enumerate() {
+            let font = egui::FontId::proportional(theme.fonts.small);
+            let format = egui::TextFormat::simple(font, color);
+            if i > 0 {
+                job.append("  ·  ", 0.0, format.clone());
+            }
+            job.append(&text, 0.0, format);
+        }
+
+        ui.label(job);
+    }
+
+    /// Draws the current toast near the top of the window until it expires
+    fn show_toast(&mut self, ctx: &Context) {
+        const TOAST_DURATION: Duration = Duration::from_secs(8);
+
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= TOAST_DURATION)
+        {
+            self.toast = None;
+        }
+
+        let Some((message, _)) = &self.toast else {
+            return;
+        };
+
+        egui::Area::new(egui::Id::new("pre_alert_toast"))
+            .anchor(egui::Align2::CENTER_TOP, vec2(0.0, 90.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style())
+                    .fill(self.theme.palette.warning)
+                    .corner_radius(8.0)
+                    .inner_margin(16.0)
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new(message)
+                                .color(self.theme.palette.toast_text)
+                                .size(self.theme.fonts.subtitle)
+                                .strong(),
+                        );
+                    });
+            });
+    }
+
+    /// Returns the reminder's accent color: the check's configured color, or the theme's primary
+    fn reminder_color(&self, reminder: &Reminder) -> Color32 {
+        match reminder {
+            Reminder::Check(check) => check.color.as_deref(),
+            Reminder::Announcement(_) => None,
+        }
+        .and_then(|hex| Color32::from_hex(hex).ok())
+        .map(|color| self.theme.readable(color))
+        .unwrap_or(self.theme.palette.primary)
+    }
+
+    /// Brings the window forward when a reminder activates and restores it afterward
+    fn update_window(&mut self, ctx: &Context) {
+        let settings = &self.config.active_window;
+        let active = matches!(self.state, ReminderState::Active(_));
+
+        if active && !self.window_raised {
+            if settings.raise {
+                ctx.send_viewport_cmd(ViewportCommand::Minimized(false));
+                ctx.send_viewport_cmd(ViewportCommand::Focus);
+                ctx.send_viewport_cmd(ViewportCommand::RequestUserAttention(
+                    egui::UserAttentionType::Critical,
+                ));
+            }
+            if settings.always_on_top {
+                ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::AlwaysOnTop));
+            }
+            self.window_raised = true;
+        } else if !active && self.window_raised {
+            if settings.raise {
+                ctx.send_viewport_cmd(ViewportCommand::RequestUserAttention(
+                    egui::UserAttentionType::Reset,
+                ));
+            }
+            if settings.always_on_top {
+                ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::Normal));
+            }
+            self.window_raised = false;
+        }
+    }
+
+    /// Draws the current time
+    fn draw_clock(&self, ui: &mut egui::Ui, now: DateTime<Local>, theme: &Theme, color: Color32) {
+        ui.heading(
+            RichText::new(format!("{:02}:{:02}", now.hour(), now.minute(),))
+                .size(theme.fonts.large)
+                .color(color),
+        );
+    }
+
+    /// Draws the idle screen: after-hours notice, or the message board, mentor on duty and timeline
+    fn draw_idle(&self, ui: &mut egui::Ui, now: DateTime<Local>, theme: &Theme) {
+        // Idle content sits in a "header area" around the middle of the screen,
+        // 600x200 at the design size and growing with the window.
+        let header_size = ui.available_size() * vec2(600.0, 200.0) / DESIGN_SIZE;
+        ui.add_space(((ui.available_height() - header_size.y) / 2.0).max(0.0));
+
+        // After hours in lab
+        ui.allocate_ui(header_size, |ui| {
+            ui.vertical_centered(|ui| {
+                if self.after_hours {
+                    ui.heading(
+                        RichText::new("After hours 😎")
+                            .color(theme.palette.after_hours)
+                            .size(theme.fonts.large)
+                            .strong(),
+                    );
+                } else {
+                    // Rotate between the greeting and any active board messages.
+                    let (text, color) = match &self.board_message {
+                        Some(message) => (
+                            message.text.as_str(),
+                            message
+                                .color
+                                .as_deref()
+                                .and_then(|hex| Color32::from_hex(hex).ok())
+                                .map(|color| theme.readable(color)),
+                        ),
+                        None => (self.config.greeting_for(now), None),
+                    };
+
+                    ui.label(
+                        RichText::new(text)
+                            .color(color.unwrap_or(theme.palette.primary))
+                            .strong()
+                            .size(theme.fonts.headline),
+                    );
+
+                    if let Some(entry) = self.config.mentor_on_duty(now) {
+                        ui.add_space(10.0);
+                        ui.label(
+                            RichText::new(format!("On duty: {}", entry.mentor))
+                                .color(theme.palette.primary)
+                                .size(theme.fonts.subtitle),
+                        );
+                    }
+
+                    ui.add_space(30.0);
+                    self.draw_timeline(ui, now, theme);
+                }
+            });
+        });
+    }
+
+    /// Draws the countdown to an upcoming reminder
+    fn draw_pending(
+        &self,
+        ui: &mut egui::Ui,
+        now: DateTime<Local>,
+        theme: &Theme,
+        reminder: &Reminder,
+    ) {
+        let seconds_until = self
+            .next_reminder(now)
+            .map(|(at, _)| (at - now).num_seconds().max(0))
+            .unwrap_or(0);
+
+        let title = match reminder {
+            Reminder::Check(_) => "Upcoming Check",
+            Reminder::Announcement(_) => "Upcoming Announcement",
+        };
+
+        let mins = seconds_until / 60;
+        let secs = seconds_until % 60;
+
+        ui.label(
+            RichText::new(title)
+                .color(theme.palette.warning)
+                .size(theme.fonts.title)
+                .strong(),
+        );
+
+        ui.label(
+            RichText::new(format!("{} in {}:{:02}", reminder, mins, secs))
+                .color(theme.palette.primary)
+                .size(theme.fonts.body),
+        );
+
+        // Progress bar over the configured pending window.
+        ui.add_space(10.0);
+        let total_pending_seconds = self.config.pending_minutes as f32 * 60.0;
+        let progress = 1.0 - (seconds_until as f32 / total_pending_seconds);
+        let progress = progress.clamp(0.0, 1.0);
+
+        ui.add(egui::ProgressBar::new(progress).desired_width(200.0));
+    }
+
+    /// Draws the Active reminder's pulsing heading, description and late notice
+    fn draw_active_heading(
+        &self,
+        ui: &mut egui::Ui,
+        theme: &Theme,
+        time: f32,
+        reminder: &Reminder,
+    ) {
+        // Pulsing effect for heading
+        let heading_alpha = theme.pulse_alpha(time);
+
+        let accent = self.reminder_color(reminder);
+        let [r, g, b, _] = accent.to_array();
+
+        let heading = match reminder {
+            Reminder::Check(_) => "⚠ Time to check in! ⚠",
+            Reminder::Announcement(_) => "📣 Announcement",
+        };
+
+        ui.heading(
+            RichText::new(heading)
+                .color(Color32::from_rgba_unmultiplied(r, g, b, heading_alpha))
+                .size(theme.fonts.headline),
+        );
+        ui.label(
+            RichText::new(format!("{}", reminder))
+                .color(accent)
+                .size(theme.fonts.subtitle),
+        );
+
+        if let Some(due) = self.late_since {
+            ui.label(
+                RichText::new(format!("Late: was due at {}", due.format("%H:%M")))
+                    .color(theme.palette.warning)
+                    .size(theme.fonts.detail),
+            );
+        }
+    }
+
+    /// Shows the public display window: clock, message board and countdowns, with no controls
+    fn show_public_display(&mut self, ctx: &Context, now: DateTime<Local>, time: f32) {
+        let settings = &self.config.public_display;
+        let mut builder = ViewportBuilder::default()
+            .with_title("Mentor Script - Lab Display")
+            .with_fullscreen(settings.fullscreen);
+        if let Some(position) = settings.position {
+            builder = builder.with_position(position);
+        }
+        let scale = settings.scale;
+
+        ctx.show_viewport_immediate(
+            ViewportId::from_hash_of("public_display"),
+            builder,
+            |ctx, _class| {
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    if self.kiosk.is_some() {
+                        ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+                    } else {
+                        self.public_display_open = false;
+                    }
+                }
+
+                // The zoom factor is shared with the main window, so this
+                // window's size is applied to the font sizes instead.
+                let zoom = ctx.zoom_factor();
+                let size = ctx.content_rect().size() * zoom;
+                let fit = (size.x / DESIGN_SIZE.x).min(size.y / DESIGN_SIZE.y);
+                let theme = self.theme.scaled(fit * scale / zoom);
+
+                let bg_color = theme.background_color(time);
+                let time_color = theme.clock_color(bg_color);
+
+                CentralPanel::default()
+                    .frame(egui::Frame::new().fill(bg_color))
+                    .show(ctx, |ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.add_space(20.0);
+                            self.draw_clock(ui, now, &theme, time_color);
+                            ui.add_space(10.0);
+
+                            match &self.state {
+                                ReminderState::Idle => self.draw_idle(ui, now, &theme),
+                                ReminderState::Pending(reminder) => {
+                                    self.draw_pending(ui, now, &theme, reminder)
+                                }
+                                ReminderState::Active(reminder) => {
+                                    self.draw_active_heading(ui, &theme, time, reminder)
+                                }
+                            }
+                        });
+                    });
+            },
+        );
+    }
+
+    /// Runs tray menu commands and updates the tray icon to match the state
+    fn update_tray(&mut self, ctx: &Context, now: DateTime<Local>) {
+        let active = matches!(self.state, ReminderState::Active(_));
+
+        while let Some(command) = self.tray.poll() {
+            match command {
+                TrayCommand::Acknowledge if active => self.acknowledge(),
+                TrayCommand::Acknowledge => {}
+                TrayCommand::Snooze => self.snooze(),
+                TrayCommand::PauseMusic => self.toggle_pause(),
+                TrayCommand::OpenSongsFolder => self.config.open_songs_folder(),
+                TrayCommand::ToggleWindow => {
+                    let minimized = ctx.input(|i| i.viewport().minimized) == Some(true);
+                    ctx.send_viewport_cmd(ViewportCommand::Minimized(!minimized));
+                    if minimized {
+                        ctx.send_viewport_cmd(ViewportCommand::Focus);
+                    }
+                }
+            }
+        }
+
+        let palette = self.theme.palette;
+        let (color, tooltip) = match &self.state {
+            ReminderState::Idle if self.after_hours => {
+                (palette.after_hours, "After hours".to_string())
+            }
+            ReminderState::Idle => (
+                palette.success,
+                match self.next_reminder(now) {
+                    Some((at, next)) => format!("Next: {next} at {}", at.format("%H:%M")),
+                    None => "No checks coming up".to_string(),
+                },
+            ),
+            ReminderState::Pending(reminder) => {
+                let seconds_until = self
+                    .next_reminder(now)
+                    .map(|(at, _)| (at - now).num_seconds().max(0))
+                    .unwrap_or(0);
+                (
+                    palette.warning,
+                    format!(
+                        "{reminder} in {}:{:02}",
+                        seconds_until / 60,
+                        seconds_until % 60
+                    ),
+                )
+            }
+            ReminderState::Active(reminder) => (palette.danger, format!("{reminder} now!")),
+        };
+        let [r, g, b, _] = color.to_array();
+
+        self.tray.set_state(TrayState {
+            color: [r, g, b],
+            tooltip,
+            reminder_active: matches!(self.state, ReminderState::Active(_)),
+            music_paused: self.current_sink.as_ref().map(|sink| sink.is_paused()),
+        });
+    }
+
+    /// Zooms the whole UI to fit the window, times the configured scale.
+    ///
+    /// A 4K display doubles everything and a small laptop screen shrinks it to fit.
+    fn apply_scale(&self, ctx: &Context) {
+        const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.5..=4.0;
+
+        // Window size in unzoomed points, so zooming doesn't feed back into itself.
+        let zoom = ctx.zoom_factor();
+        let size = ctx.content_rect().size() * zoom;
+        let fit = (size.x / DESIGN_SIZE.x).min(size.y / DESIGN_SIZE.y);
+        let target = (fit * self.config.scale).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end());
+
+        if (target - zoom).abs() > 0.01 {
+            ctx.set_zoom_factor(target);
+        }
+    }
+
+    /// Returns true if the Active reminder should flash the screen border
+    fn should_flash(&self) -> bool {
+        matches!(self.state, ReminderState::Active(_))
+            && match self.config.accessibility.flash {
+                FlashAlerts::Never => false,
+                FlashAlerts::WithoutAudio => self.audio.status() != AudioStatus::Ready,
+                FlashAlerts::Always => true,
+            }
+    }
+
+    /// Draws a thick border in the reminder's color, on for half of each second.
+    ///
+    /// One flash per second stays well under the WCAG limit of three. With
+    /// reduced motion the border stays on instead of flashing.
+    fn draw_flash(&self, ctx: &Context, time: f32) {
+        let ReminderState::Active(reminder) = &self.state else {
+            return;
+        };
+        if !self.theme.reduced_motion && time.fract() >= 0.5 {
+            return;
+        }
+
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("flash_alert"),
+        ));
+        painter.rect_stroke(
+            ctx.content_rect(),
+            0.0,
+            egui::Stroke::new(24.0, self.reminder_color(reminder)),
+            egui::StrokeKind::Inside,
+        );
     }
 }
 
 impl eframe::App for MentorApp {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
         self.update_state();
-        if ctx.input(|i| i.key_pressed(egui::Key::A)) {
-            self.state = ReminderState::Active(CheckType::Hour);
-            self.trigger_consumed = true;
-        }
+        self.handle_shortcuts(ctx);
 
-        let now = Local::now();
-        let _minute = now.minute();
+        let now = self.clock.now();
+        // Picked once per frame so both windows show the same message.
+        self.board_message = self.message_board.current(now);
+
+        self.update_tray(ctx, now);
+        self.update_window(ctx);
+        self.apply_scale(ctx);
 
         let time: f32 = ctx.input(|i| i.time) as f32; // variable time for dynamic color
-        let bg_color = self.background_color(time);
-        let time_color = Self::invert_color(bg_color);
+        let bg_color = self.theme.background_color(time);
+        let time_color = self.theme.clock_color(bg_color);
+
+        // Audio status on the left and the songs folder button on the right,
+        // in their own panel so they never overlap the reminder content.
+        let (status_text, status_color) = match self.audio.status() {
+            AudioStatus::NotStarted => ("🔈 Audio standby", self.theme.palette.muted),
+            AudioStatus::Ready => (
+                "🔊 Audio ready",
+                self.theme.readable(self.theme.palette.success),
+            ),
+            AudioStatus::Unavailable => {
+                ("🔇 Audio unavailable, retrying", self.theme.palette.danger)
+            }
+        };
+        TopBottomPanel::bottom("status_bar")
+            .frame(egui::Frame::new().fill(bg_color).inner_margin(15.0))
+            .show_separator_line(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new(status_text)
+                            .color(status_color)
+                            .size(self.theme.fonts.small),
+                    );
+
+                    if let Some(help_key) = self.keymap.label(ctx, KeyAction::Help) {
+                        ui.add_space(20.0);
+                        ui.label(
+                            RichText::new(format!("{help_key}: shortcuts"))
+                                .color(self.theme.palette.muted)
+                                .size(self.theme.fonts.small),
+                        );
+                    }
+
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        let folder_button =
+                            Button::new(RichText::new("📁 Songs").size(self.theme.fonts.detail))
+                                .fill(self.theme.palette.info.gamma_multiply(0.7))
+                                .min_size(vec2(140.0, 35.0))
+                                .corner_radius(8.0);
+
+                        if ui.add(folder_button).clicked() {
+                            Config::open_songs_folder(&self.config);
+                        }
+                    });
+                });
+            });
 
         CentralPanel::default()
             .frame(egui::Frame::new().fill(bg_color))
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.add_space(20.0);
-                    ui.heading(
-                        RichText::new(format!("{:02}:{:02}", now.hour(), now.minute(),))
-                            .size(42.0)
-                            .color(time_color),
-                    );
-
+                    self.draw_clock(ui, now, &self.theme, time_color);
                     ui.add_space(10.0);
 
-                    // Reserve a consistent "header area" that we can fill differently based on time/state.
-                    let rect = ctx.content_rect();
-                    let center = rect.center();
-                    let header_rect =
-                        egui::Rect::from_center_size(center, egui::vec2(600.0, 200.0));
-
-                    match self.state {
-                        ReminderState::Idle => {
-                            // After hours in lab
-                            #[allow(deprecated)]
-                            ui.allocate_ui_at_rect(header_rect, |ui| {
-                                ui.vertical_centered(|ui| {
-                                    if self.after_hours {
-                                        ui.heading(
-                                            RichText::new("After hours 😎")
-                                                .color(Color32::from_hex("#3c7a89").unwrap())
-                                                .size(42.0)
-                                                .strong(),
-                                        );
-                                    } else {
-                                        ui.label(
-                                            RichText::new(&self.config.mentor_text)
-                                                .color(Color32::from_hex("#23F123").unwrap())
-                                                .strong()
-                                                .size(48.0),
-                                        );
-                                    };
-                                });
-                            });
-                        }
+                    match self.state.clone() {
+                        ReminderState::Idle => self.draw_idle(ui, now, &self.theme),
 
-                        ReminderState::Pending(check) => {
-                            let (_, minutes_until) = minutes_until_next_check(now);
+                        ReminderState::Pending(reminder) => {
+                            self.draw_pending(ui, now, &self.theme, &reminder)
+                        }
 
-                            // Convert "minutes until next check (rounded down to minute)" into seconds-until.
-                            // If next check is at the next minute boundary, this works well:
-                            // seconds_until = minutes_until*60 - current_seconds.
-                            let seconds_until = (minutes_until * 60 - now.second() as i64).max(0);
+                        ReminderState::Active(reminder) => {
+                            self.draw_active_heading(ui, &self.theme, time, &reminder);
 
-                            let mins = seconds_until / 60;
-                            let secs = seconds_until % 60;
+                            let form_link = self.form_link(now);
+                            let done_text = match &reminder {
+                                Reminder::Check(_) => "Checked",
+                                Reminder::Announcement(_) => "Dismiss",
+                            };
 
-                            ui.label(
-                                RichText::new("Upcoming Check")
-                                    .color(Color32::from_hex("#f39c12").unwrap())
-                                    .size(28.0)
-                                    .strong(),
-                            );
+                            // Now playing: title, artist and progress through the track
+                            if let (Some(track), Some(sink)) =
+                                (self.now_playing.as_ref(), self.current_sink.as_ref())
+                            {
+                                ui.add_space(20.0);
 
-                            ui.label(
-                                RichText::new(format!("{} in {}:{:02}", check, mins, secs))
-                                    .color(Color32::from_hex("#23F123").unwrap())
-                                    .size(20.0),
-                            );
+                                let title = match &track.artist {
+                                    Some(artist) => format!("♪ {} — {}", track.title, artist),
+                                    None => format!("♪ {}", track.title),
+                                };
+                                ui.label(
+                                    RichText::new(title)
+                                        .color(self.theme.palette.primary)
+                                        .size(self.theme.fonts.detail),
+                                );
 
-                            // Progress bar over the 5-minute pending window (300s).
-                            ui.add_space(10.0);
-                            let total_pending_seconds = 5.0 * 60.0;
-                            let progress = 1.0 - (seconds_until as f32 / total_pending_seconds);
-                            let progress = progress.clamp(0.0, 1.0);
+                                let position = sink.get_pos();
+                                let (progress, text) = match track.duration {
+                                    Some(total) if !total.is_zero() => (
+                                        (position.as_secs_f32() / total.as_secs_f32())
+                                            .clamp(0.0, 1.0),
+                                        format!(
+                                            "{} / {}",
+                                            Self::format_duration(position),
+                                            Self::format_duration(total)
+                                        ),
+                                    ),
+                                    _ => (0.0, Self::format_duration(position)),
+                                };
 
-                            ui.add(egui::ProgressBar::new(progress).desired_width(200.0));
-                        }
+                                ui.add(
+                                    egui::ProgressBar::new(progress)
+                                        .desired_width(300.0)
+                                        .text(text),
+                                );
+                            }
 
-                        ReminderState::Active(check) => {
-                            // Pulsing effect for heading
-                            let pulse = (time * 2.0).sin() * 0.15 + 0.85;
-                            let heading_alpha = (pulse * 255.0) as u8;
-
-                            ui.heading(
-                                RichText::new("⚠ Time to check in! ⚠")
-                                    .color(Color32::from_rgba_unmultiplied(
-                                        35,
-                                        241,
-                                        35,
-                                        heading_alpha,
-                                    ))
-                                    .size(48.0),
-                            );
-                            ui.label(
-                                RichText::new(format!("{}", check))
-                                    .color(Color32::from_hex("#23F123").unwrap())
-                                    .size(24.0),
-                            );
-
-                            ui.add_space(60.0);
+                            ui.add_space(40.0);
 
                             // Centered button layout using relative spacing
                             ui.vertical(|ui| {
@@ -252,51 +1378,66 @@ impl eframe::App for MentorApp {
                                 let button_width = 120.0;
                                 let gap = 20.0;
                                 let total_width = button_width * 2.0 + gap;
-                                let left_padding = (available_width - total_width) / 2.0;
+                                // The QR code spans both rows, to the left of "Open Form".
+                                let qr_size = 60.0 * 2.0 + 14.0;
+                                let qr_link = form_link.as_ref().filter(|_| self.config.form_qr);
+                                let qr_space = if qr_link.is_some() {
+                                    qr_size + gap
+                                } else {
+                                    0.0
+                                };
+                                let left_padding =
+                                    (available_width - total_width - qr_space) / 2.0 + qr_space;
 
                                 // Row 1: Open Form + Checked
-                                ui.horizontal(|ui| {
+                                let row = ui.horizontal(|ui| {
                                     ui.add_space(left_padding);
 
                                     let open_button = egui::Button::new(
-                                        RichText::new("Open Form").size(16.0).strong(),
+                                        self.theme.button_text("Open Form", self.theme.fonts.small),
                                     )
-                                    .fill(Color32::from_hex("#3498db").unwrap())
+                                    .fill(self.theme.palette.info)
                                     .min_size(egui::vec2(button_width, 60.0))
                                     .corner_radius(8.0);
 
-                                    if ui.add(open_button).clicked() {
-                                        let url = match check {
-                                            CheckType::Hour => &self.config.hourly_link,
-                                            CheckType::HalfHour => &self.config.thirty_link,
-                                        };
-
-                                        let _ = webbrowser::open(url);
+                                    let open = ui.add_enabled(form_link.is_some(), open_button);
+                                    if open.clicked() {
+                                        self.open_form();
                                     }
 
                                     ui.add_space(gap);
 
-                                    let checked_button =
-                                        Button::new(RichText::new("Checked").size(16.0).strong())
-                                            .fill(Color32::from_hex("#27ae60").unwrap())
-                                            .min_size(egui::vec2(button_width, 60.0))
-                                            .corner_radius(8.0);
+                                    let checked_button = Button::new(
+                                        self.theme.button_text(done_text, self.theme.fonts.small),
+                                    )
+                                    .fill(self.theme.palette.success)
+                                    .min_size(egui::vec2(button_width, 60.0))
+                                    .corner_radius(8.0);
 
                                     if ui.add(checked_button).clicked() {
-                                        if let Some(sink) = self.current_sink.take() {
-                                            sink.stop();
-                                            sink.detach();
-                                        }
-                                        self.state = ReminderState::Idle;
+                                        self.acknowledge();
                                     }
+
+                                    open.rect
                                 });
 
+                                if let Some(link) = qr_link {
+                                    let open_rect = row.inner;
+                                    let rect = egui::Rect::from_min_size(
+                                        open_rect.left_top() - vec2(qr_space, 0.0),
+                                        vec2(qr_size, qr_size),
+                                    );
+                                    // A child Ui so the code doesn't push the second row down.
+                                    let mut qr_ui =
+                                        ui.new_child(egui::UiBuilder::new().max_rect(rect));
+                                    qr::qr_code(&mut qr_ui, link, qr_size);
+                                }
+
                                 ui.add_space(14.0);
 
-                                // Row 2: Pause/Resume centered under the gap between the two buttons
+                                // Row 2: Pause/Resume + Skip, aligned with the row above
                                 ui.horizontal(|ui| {
-                                    // center is left_padding + button_width + (gap / 2.0)
-                                    let pause_center_x = left_padding + button_width + (gap / 2.0);
+                                    ui.add_space(left_padding);
 
                                     let is_paused = self
                                         .current_sink
@@ -310,23 +1451,31 @@ impl eframe::App for MentorApp {
                                         "Pause Music"
                                     };
 
-                                    let pause_button =
-                                        Button::new(RichText::new(pause_text).size(16.0).strong())
-                                            .fill(Color32::from_hex("#780000").unwrap())
-                                            .min_size(vec2(button_width, 60.0))
-                                            .corner_radius(8.0);
+                                    let pause_button = Button::new(
+                                        self.theme.button_text(pause_text, self.theme.fonts.small),
+                                    )
+                                    .fill(self.theme.palette.music)
+                                    .min_size(vec2(button_width, 60.0))
+                                    .corner_radius(8.0);
+
+                                    if ui.add(pause_button).clicked() {
+                                        self.toggle_pause();
+                                    }
 
-                                    // place left edge so the button is centered under the middle gap
-                                    ui.add_space((pause_center_x - (button_width / 2.0)).max(0.0));
+                                    ui.add_space(gap);
 
-                                    if ui.add(pause_button).clicked()
-                                        && let Some(sink) = self.current_sink.as_ref()
+                                    let skip_button = Button::new(
+                                        self.theme.button_text("Skip ⏭", self.theme.fonts.small),
+                                    )
+                                    .fill(self.theme.palette.music)
+                                    .min_size(vec2(button_width, 60.0))
+                                    .corner_radius(8.0);
+
+                                    if ui
+                                        .add_enabled(!self.song_queue.is_empty(), skip_button)
+                                        .clicked()
                                     {
-                                        if sink.is_paused() {
-                                            sink.play();
-                                        } else {
-                                            sink.pause();
-                                        }
+                                        self.play_next();
                                     }
                                 });
                             });
@@ -335,45 +1484,343 @@ impl eframe::App for MentorApp {
 
                     ui.add_space(20.0);
                 });
+            });
 
-                // Add small "Open Songs Folder" button in bottom right corner
-                let rect = ctx.content_rect();
-                let button_size = egui::vec2(140.0, 35.0);
-                let margin = 15.0;
-                #[allow(deprecated)]
-                ui.allocate_ui_at_rect(
-                    egui::Rect::from_min_size(
-                        egui::Pos2::new(
-                            rect.max.x - button_size.x - margin,
-                            rect.max.y - button_size.y - margin,
-                        ),
-                        button_size,
-                    ),
-                    |ui| {
-                        ui.horizontal_centered(|ui| {
-                            let folder_button =
-                                egui::Button::new(RichText::new("📁 Songs").size(18.0))
-                                    .fill(Color32::from_rgba_unmultiplied(52, 152, 219, 180))
-                                    .min_size(button_size)
-                                    .corner_radius(8.0);
+        self.show_toast(ctx);
+        self.show_form_fallback(ctx);
 
-                            if ui.add(folder_button).clicked() {
-                                Config::open_songs_folder(&self.config);
-                            }
-                        })
-                    },
-                );
-            });
+        if self.help_open {
+            self.keymap.show_help(ctx, &mut self.help_open);
+        }
+
+        if self.config.debug {
+            self.draw_debug_panel(ctx, now);
+        }
+
+        if let Some(kiosk) = &mut self.kiosk {
+            kiosk.update(ctx, &self.config);
+        }
+
+        if self.public_display_open {
+            self.show_public_display(ctx, now, time);
+        }
+
+        if self.should_flash() {
+            self.draw_flash(ctx, time);
+        }
 
         let repaint_delay = match self.state {
-            ReminderState::Idle => Duration::from_millis(33), // smooth breathing
-            ReminderState::Pending(_) => Duration::from_secs(1), // repaint once per second
-            ReminderState::Active(_) => Duration::from_millis(33), // smooth pulsing
+            // A sped-up clock needs frequent updates so trigger minutes aren't skipped.
+            _ if !self.clock.is_real_time() => Duration::from_millis(33),
+            _ if self.theme.reduced_motion => Duration::from_secs(1), // nothing animates
+            ReminderState::Idle => Duration::from_millis(33),         // smooth breathing
+            ReminderState::Pending(_) => Duration::from_secs(1),      // repaint once per second
+            ReminderState::Active(_) => Duration::from_millis(33),    // smooth pulsing
         };
 
         ctx.request_repaint_after(repaint_delay);
     }
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        self.audio = None; // Safely drop audio stream
+        self.audio.close(); // Safely drop audio stream
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::{ActionSender, Notification, NotificationBackend};
+    use crate::test_util;
+    use std::sync::mpsc::Receiver;
+    use std::sync::{Arc, Mutex};
+
+    /// Stands in for the session bus, keeping each notification's action sender
+    #[derive(Clone, Default)]
+    struct FakeBus(Arc<Mutex<Vec<(Notification, ActionSender)>>>);
+
+    impl NotificationBackend for FakeBus {
+        fn show(&self, notification: Notification, actions: ActionSender, _close: Receiver<()>) {
+            self.0.lock().unwrap().push((notification, actions));
+        }
+    }
+
+    impl FakeBus {
+        /// Presses a button on the most recent notification
+        fn press(&self, action: NotificationAction) {
+            let shown = self.0.lock().unwrap();
+            shown.last().expect("no notification shown").1.send(action);
+        }
+    }
+
+    fn app() -> (MentorApp, FakeBus) {
+        app_with(r#"{ "mentor_text": "Hi" }"#)
+    }
+
+    /// App built from a config.json body, showing notifications on a fake bus
+    fn app_with(config: &str) -> (MentorApp, FakeBus) {
+        let config: Config = serde_json::from_str(config).unwrap();
+        let ctx = Context::default();
+        let mut app = MentorApp::new(config, &ctx);
+        let bus = FakeBus::default();
+        app.notifier = Notifier::with_backend(Some(Box::new(bus.clone())), &ctx);
+        app.shown_announcements = ShownAnnouncements::default();
+        (app, bus)
+    }
+
+    fn check() -> Reminder {
+        Reminder::Check(test_util::check("half_hour", "Half hour check", &[]))
+    }
+
+    #[test]
+    fn checked_acknowledges_active_reminder() {
+        let (mut app, bus) = app();
+        app.state = ReminderState::Active(check());
+        app.notify_state(Local::now());
+        assert!(bus.0.lock().unwrap()[0].0.urgent);
+
+        bus.press(NotificationAction::Checked);
+        app.handle_notification_actions();
+
+        assert_eq!(app.state, ReminderState::Idle);
+        assert!(app.snoozed.is_none());
+    }
+
+    #[test]
+    fn snooze_puts_off_active_reminder() {
+        let (mut app, bus) = app();
+        app.state = ReminderState::Active(check());
+        app.notify_state(Local::now());
+
+        bus.press(NotificationAction::Snooze);
+        app.handle_notification_actions();
+
+        assert_eq!(app.state, ReminderState::Idle);
+        let snoozed = app.snoozed.as_ref().expect("reminder not snoozed");
+        assert_eq!(snoozed.reminder, check());
+        assert!(snoozed.until > Local::now());
+    }
+
+    #[test]
+    fn buttons_on_pending_reminder_are_deferred() {
+        let (mut app, bus) = app();
+        app.state = ReminderState::Pending(check());
+        app.notify_state(Local::now());
+
+        bus.press(NotificationAction::Snooze);
+        app.handle_notification_actions();
+
+        assert_eq!(app.state, ReminderState::Pending(check()));
+        assert_eq!(
+            app.deferred_action,
+            Some((check(), NotificationAction::Snooze))
+        );
+    }
+
+    #[test]
+    fn buttons_on_replaced_notifications_are_ignored() {
+        let (mut app, bus) = app();
+        app.state = ReminderState::Active(check());
+        app.notify_state(Local::now());
+        let stale = bus.0.lock().unwrap()[0].1.clone();
+        app.notify_state(Local::now());
+
+        stale.send(NotificationAction::Checked);
+        app.handle_notification_actions();
+
+        assert_eq!(app.state, ReminderState::Active(check()));
+    }
+
+    const MONDAY: (i32, u32, u32) = (2026, 6, 1);
+
+    /// Runs the state machine with the clock set a few seconds into the given minute
+    fn run_at(app: &mut MentorApp, hour: u32, minute: u32) {
+        app.clock
+            .set(test_util::local(MONDAY, hour, minute) + chrono::Duration::seconds(5));
+        app.update_state();
+    }
+
+    fn active_id(app: &MentorApp) -> Option<&str> {
+        match &app.state {
+            ReminderState::Active(Reminder::Check(check)) => Some(&check.id),
+            _ => None,
+        }
+    }
+
+    const BACK_TO_BACK: &str = r#"{
+        "mentor_text": "Hi",
+        "check_types": [
+            { "id": "first", "label": "First", "triggers": [":30"] },
+            { "id": "second", "label": "Second", "triggers": [":31"] }
+        ]
+    }"#;
+
+    #[test]
+    fn check_on_the_next_minute_goes_off_after_acknowledging() {
+        let (mut app, _) = app_with(BACK_TO_BACK);
+
+        run_at(&mut app, 14, 30);
+        assert_eq!(active_id(&app), Some("first"));
+        app.acknowledge();
+        run_at(&mut app, 14, 30);
+        assert_eq!(active_id(&app), None, "acknowledged check went off again");
+
+        run_at(&mut app, 14, 31);
+        assert_eq!(active_id(&app), Some("second"));
+    }
+
+    #[test]
+    fn check_due_while_another_is_active_is_queued() {
+        let (mut app, _) = app_with(BACK_TO_BACK);
+
+        run_at(&mut app, 14, 30);
+        run_at(&mut app, 14, 31);
+        assert_eq!(active_id(&app), Some("first"));
+        assert_eq!(app.queued_checks.len(), 1);
+
+        app.acknowledge();
+        run_at(&mut app, 14, 31);
+        assert_eq!(active_id(&app), Some("second"));
+        assert_eq!(app.late_since, Some(test_util::local(MONDAY, 14, 31)));
+
+        app.acknowledge();
+        run_at(&mut app, 14, 32);
+        assert_eq!(active_id(&app), None);
+    }
+
+    const TECH_TALK: &str = r#"{
+        "mentor_text": "Hi",
+        "check_types": [],
+        "announcements": [{ "at": "2026-06-01T14:40:00", "message": "Tech talk" }]
+    }"#;
+
+    fn announcing(app: &MentorApp) -> bool {
+        matches!(app.state, ReminderState::Active(Reminder::Announcement(_)))
+    }
+
+    #[test]
+    fn shown_announcement_stays_shown_after_a_restart() {
+        let path = std::env::temp_dir().join(format!(
+            "mentor-script-shown-announcements-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let (mut app, _) = app_with(TECH_TALK);
+        app.shown_announcements = ShownAnnouncements::load(path.clone());
+        run_at(&mut app, 14, 40);
+        assert!(announcing(&app));
+        app.acknowledge();
+
+        let (mut restarted, _) = app_with(TECH_TALK);
+        restarted.shown_announcements = ShownAnnouncements::load(path.clone());
+        run_at(&mut restarted, 14, 42);
+        assert!(!announcing(&restarted), "announcement shown again");
+        assert!(restarted.announcements.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn announcement_expires_after_max_late_minutes() {
+        let (mut app, _) = app_with(TECH_TALK);
+        run_at(&mut app, 14, 45);
+        assert!(announcing(&app), "late announcement not shown");
+
+        let (mut app, _) = app_with(TECH_TALK);
+        run_at(&mut app, 14, 51);
+        assert!(!announcing(&app));
+        assert!(app.announcements.is_empty());
+    }
+
+    #[test]
+    fn each_pre_alert_fires_once() {
+        let (mut app, _) = app_with(
+            r#"{
+                "mentor_text": "Hi",
+                "check_types": [{ "id": "half_hour", "label": "Half hour", "triggers": [":30"] }],
+                "pre_alerts": [
+                    { "minutes_before": 2, "kind": "toast", "message": "Two" },
+                    { "minutes_before": 1, "kind": "toast", "message": "One" }
+                ]
+            }"#,
+        );
+
+        let mut toasts = Vec::new();
+        let start = test_util::local(MONDAY, 14, 27);
+        for seconds in (0..180).step_by(5) {
+            app.clock.set(start + chrono::Duration::seconds(seconds));
+            app.update_state();
+            toasts.extend(app.toast.take().map(|(message, _)| message));
+        }
+
+        assert_eq!(toasts, ["Two", "One"]);
+    }
+
+    const EVERY_TEN_MINUTES: &str = r#"{
+        "mentor_text": "Hi",
+        "check_types": [
+            { "id": "first", "label": "First", "triggers": [":30"] },
+            { "id": "second", "label": "Second", "triggers": [":40"] }
+        ]
+    }"#;
+
+    #[test]
+    fn recent_missed_check_goes_off_late() {
+        let (mut app, _) = app_with(EVERY_TEN_MINUTES);
+
+        // The machine slept through 14:30.
+        run_at(&mut app, 14, 25);
+        run_at(&mut app, 14, 35);
+
+        assert_eq!(active_id(&app), Some("first"));
+        assert_eq!(app.late_since, Some(test_util::local(MONDAY, 14, 30)));
+    }
+
+    #[test]
+    fn only_the_latest_missed_check_goes_off() {
+        let (mut app, _) = app_with(EVERY_TEN_MINUTES);
+        app.handle_missed_checks(
+            test_util::local(MONDAY, 14, 25),
+            test_util::local(MONDAY, 14, 45),
+        );
+
+        assert_eq!(active_id(&app), Some("second"));
+        assert_eq!(app.late_since, Some(test_util::local(MONDAY, 14, 40)));
+    }
+
+    /// Config with one check at :30 and the given `missed_checks` settings
+    fn half_hourly(missed_checks: &str) -> String {
+        format!(
+            r#"{{
+                "mentor_text": "Hi",
+                "check_types": [{{ "id": "half_hour", "label": "Half hour", "triggers": [":30"] }}],
+                "missed_checks": {missed_checks}
+            }}"#
+        )
+    }
+
+    #[test]
+    fn missed_check_past_max_late_is_only_logged() {
+        let config = half_hourly(r#"{ "max_late_minutes": 10 }"#);
+        let missed_at = |hour, minute| {
+            let (mut app, _) = app_with(&config);
+            app.handle_missed_checks(
+                test_util::local(MONDAY, 14, 25),
+                test_util::local(MONDAY, hour, minute),
+            );
+            active_id(&app).map(str::to_string)
+        };
+
+        assert_eq!(missed_at(14, 40).as_deref(), Some("half_hour"));
+        assert_eq!(missed_at(14, 41), None);
+    }
+
+    #[test]
+    fn log_policy_never_activates_missed_checks() {
+        let (mut app, _) = app_with(&half_hourly(r#"{ "policy": "log" }"#));
+        app.handle_missed_checks(
+            test_util::local(MONDAY, 14, 25),
+            test_util::local(MONDAY, 14, 35),
+        );
+        assert_eq!(active_id(&app), None);
     }
 }
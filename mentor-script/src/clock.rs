@@ -0,0 +1,138 @@
+//! Time source for the reminder logic
+//!
+//! Normally the wall clock. The debug panel can shift it or run it faster so
+//! the whole reminder flow can be tried out in seconds.
+
+use chrono::{DateTime, Duration, Local};
+
+/// Wall clock with an adjustable offset and speed
+#[derive(Debug, Clone, Copy)]
+pub struct Clock {
+    /// Real time when the offset or speed last changed
+    anchor_real: DateTime<Local>,
+    /// Reported time at `anchor_real`
+    anchor_time: DateTime<Local>,
+    /// Reported seconds per real second
+    speed: f64,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        let now = Local::now();
+        Self {
+            anchor_real: now,
+            anchor_time: now,
+            speed: 1.0,
+        }
+    }
+}
+
+impl Clock {
+    /// Returns the current time as seen by the app
+    pub fn now(&self) -> DateTime<Local> {
+        let real = Local::now();
+        if self.is_real_time() {
+            return real;
+        }
+
+        let elapsed = (real - self.anchor_real).as_seconds_f64() * self.speed;
+        self.anchor_time + Duration::milliseconds((elapsed * 1000.0) as i64)
+    }
+
+    /// Returns true if the clock matches the wall clock
+    pub fn is_real_time(&self) -> bool {
+        self.speed == 1.0 && self.anchor_time == self.anchor_real
+    }
+
+    /// Reported seconds per real second
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Changes how fast time passes, keeping the current time
+    pub fn set_speed(&mut self, speed: f64) {
+        self.reanchor(self.now());
+        self.speed = speed;
+    }
+
+    /// Moves the clock forward (or back, if negative)
+    pub fn shift(&mut self, by: Duration) {
+        self.reanchor(self.now() + by);
+    }
+
+    /// Sets the clock to the given time, keeping the speed
+    pub fn set(&mut self, to: DateTime<Local>) {
+        self.reanchor(to);
+    }
+
+    /// Goes back to the wall clock at normal speed
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn reanchor(&mut self, time: DateTime<Local>) {
+        self.anchor_real = Local::now();
+        self.anchor_time = time;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::local;
+
+    /// Allowed drift between two readings taken a moment apart
+    const TOLERANCE: Duration = Duration::seconds(1);
+
+    fn close_to(a: DateTime<Local>, b: DateTime<Local>) -> bool {
+        (a - b).abs() < TOLERANCE
+    }
+
+    /// Pretends `seconds` of real time passed since the clock was last anchored
+    fn wait(clock: &mut Clock, seconds: i64) {
+        clock.anchor_real -= Duration::seconds(seconds);
+    }
+
+    #[test]
+    fn follows_the_wall_clock_by_default() {
+        let clock = Clock::default();
+        assert!(clock.is_real_time());
+        assert!(close_to(clock.now(), Local::now()));
+    }
+
+    #[test]
+    fn set_time_keeps_running_from_the_anchor() {
+        let start = local((2026, 6, 1), 14, 25);
+        let mut clock = Clock::default();
+        clock.set(start);
+        assert!(!clock.is_real_time());
+        assert!(close_to(clock.now(), start));
+
+        wait(&mut clock, 30);
+        assert!(close_to(clock.now(), start + Duration::seconds(30)));
+
+        clock.shift(Duration::minutes(5));
+        assert!(close_to(clock.now(), start + Duration::seconds(330)));
+    }
+
+    #[test]
+    fn speed_scales_elapsed_time_without_jumping() {
+        let start = local((2026, 6, 1), 14, 25);
+        let mut clock = Clock::default();
+        clock.set(start);
+
+        clock.set_speed(60.0);
+        assert_eq!(clock.speed(), 60.0);
+        assert!(
+            close_to(clock.now(), start),
+            "changing speed moved the clock"
+        );
+
+        wait(&mut clock, 10);
+        assert!(close_to(clock.now(), start + Duration::minutes(10)));
+
+        clock.reset();
+        assert!(clock.is_real_time());
+        assert_eq!(clock.speed(), 1.0);
+    }
+}
@@ -2,23 +2,104 @@
 //!
 //! Loads application settings from config.json located next to the executable.
 
+use crate::announcement::Announcement;
+use crate::keymap::KeymapConfig;
+use crate::kiosk::KioskConfig;
+use crate::message_board::BoardMessage;
+use crate::playlist;
+use crate::roster::{self, RosterEntry};
+use crate::scheduler::{CheckDefinition, CheckType, LabHours, Schedule, Trigger};
+use crate::theme::ThemeConfig;
+use chrono::{DateTime, Local, NaiveDate};
+use eframe::egui::Color32;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use serde::Deserialize;
+use std::collections::HashSet;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Command that opens a link, written as one string or a list of arguments
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum BrowserCommand {
+    /// Split into arguments on spaces, keeping "double" or 'single' quoted parts together
+    Line(String),
+    /// Program followed by its arguments, used as written
+    Args(Vec<String>),
+}
+
+impl BrowserCommand {
+    /// Returns the program followed by its arguments
+    pub fn args(&self) -> Result<Vec<String>, String> {
+        let args = match self {
+            BrowserCommand::Line(line) => split_command(line)?,
+            BrowserCommand::Args(args) => args.clone(),
+        };
+
+        match args.first() {
+            Some(program) if !program.trim().is_empty() => Ok(args),
+            _ => Err("browser command is empty".to_string()),
+        }
+    }
+}
+
+/// Splits a command line into arguments.
+///
+/// Quotes group words and are removed; backslashes are kept as written so
+/// Windows paths work.
+fn split_command(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.get_or_insert_default().push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_default().push(c),
+        }
+    }
+
+    if let Some(q) = quote {
+        return Err(format!(
+            "unterminated {q} quote in browser command `{line}`"
+        ));
+    }
+    args.extend(current);
+    Ok(args)
+}
+
 /// Application configuration loaded from config.json
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     /// Message to display at the bottom of the window
     pub mentor_text: String,
-    /// URL to open for hourly check-ins
+    /// URL to open for hourly check-ins (used by the default check types)
+    #[serde(default)]
     pub hourly_link: String,
-    /// URL to open for 30-minute check-ins
+    /// URL to open for 30-minute check-ins (used by the default check types)
+    #[serde(default)]
     pub thirty_link: String,
 
+    /// Kinds of checks and when they go off.
+    ///
+    /// When empty, the standard half hour (:30) and hourly (:55) checks are
+    /// used with `thirty_link` and `hourly_link`.
+    #[serde(default)]
+    pub check_types: Vec<CheckDefinition>,
+
     /// Folder containing audio files (can be anywhere).
     ///
+    /// Subfolders are included, and M3U/PLS playlists found inside are read
+    /// as song sources. This may also point directly at a playlist file.
+    ///
     /// If relative, it is resolved relative to the executable's directory.
     #[serde(default, alias = "SONG_FOLDER")]
     pub songs_dir: PathBuf,
@@ -26,23 +107,277 @@ pub struct Config {
     /// Audio files discovered from `songs_dir`
     #[serde(skip)]
     pub songs: Vec<PathBuf>,
+
+    /// Name of the audio output device to play through.
+    ///
+    /// Matched case-insensitively, first exactly and then as a substring.
+    /// Falls back to the system default device when unset or not found.
+    #[serde(default)]
+    pub audio_device: Option<String>,
+
+    /// Daily hours the lab is open; checks only happen in this window
+    #[serde(default)]
+    pub lab_hours: LabHours,
+
+    /// Dates the lab is closed all day (e.g. "2026-11-26")
+    #[serde(default)]
+    pub closures: Vec<NaiveDate>,
+
+    /// Messages the idle screen rotates through along with the greeting
+    #[serde(default)]
+    pub messages: Vec<BoardMessage>,
+
+    /// JSON file with more board messages, re-read when it changes.
+    ///
+    /// If relative, it is resolved relative to the executable's directory.
+    #[serde(default)]
+    pub messages_file: Option<PathBuf>,
+
+    /// One-off announcements shown once at a set date and time
+    #[serde(default)]
+    pub announcements: Vec<Announcement>,
+
+    /// How many minutes before a check the Pending countdown appears
+    #[serde(default = "default_pending_minutes")]
+    pub pending_minutes: i64,
+
+    /// How many minutes a snoozed reminder stays quiet before going off again
+    #[serde(default = "default_snooze_minutes")]
+    pub snooze_minutes: i64,
+
+    /// Show desktop notifications for upcoming and active reminders
+    #[serde(default)]
+    pub notifications: bool,
+
+    /// Show a tray icon with the reminder state and quick actions
+    #[serde(default)]
+    pub tray_icon: bool,
+
+    /// Command used to open form links instead of the system default browser.
+    ///
+    /// `{url}` is replaced by the link, which is appended when there's no
+    /// `{url}` (e.g. "firefox --new-window").
+    #[serde(default)]
+    pub browser: Option<BrowserCommand>,
+
+    /// Show a QR code of the form link next to "Open Form", for filling it out on a phone
+    #[serde(default)]
+    pub form_qr: bool,
+
+    /// Extra alerts (chime or toast) at set times before each check
+    #[serde(default)]
+    pub pre_alerts: Vec<PreAlert>,
+
+    /// What to do about checks whose trigger minute passed unnoticed
+    #[serde(default)]
+    pub missed_checks: MissedChecks,
+
+    /// Shift roster mapping weekdays and times to the mentor on duty
+    #[serde(default)]
+    pub roster: Vec<RosterEntry>,
+
+    /// Colors, font sizes and background style
+    #[serde(default)]
+    pub theme: ThemeConfig,
+
+    /// Second window mirroring the message board and countdowns, e.g. on the lab TV
+    #[serde(default)]
+    pub public_display: PublicDisplay,
+
+    /// Keyboard shortcuts for reminder actions
+    #[serde(default)]
+    pub keys: KeymapConfig,
+
+    /// Enables debug tools: the debug panel and the shortcut that starts a check on demand
+    #[serde(default)]
+    pub debug: bool,
+
+    /// Fullscreen lab display mode that can't be closed without the unlock key
+    #[serde(default)]
+    pub kiosk: KioskConfig,
+
+    /// How the window gets attention while a reminder is Active
+    #[serde(default)]
+    pub active_window: ActiveWindow,
+
+    /// Multiplier on the UI's automatic scaling to the window size
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
+    /// Reduced motion, high contrast and visual alerts
+    #[serde(default)]
+    pub accessibility: Accessibility,
+
+    /// Where this config was loaded from
+    #[serde(skip)]
+    pub config_path: PathBuf,
+}
+
+fn default_pending_minutes() -> i64 {
+    5
+}
+
+fn default_snooze_minutes() -> i64 {
+    5
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+/// How a pre-alert gets the mentor's attention
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PreAlertKind {
+    /// Play a short chime
+    Chime,
+    /// Show a message on screen for a few seconds
+    Toast,
+}
+
+/// An alert given a set number of minutes before each check
+#[derive(Debug, Deserialize, Clone)]
+pub struct PreAlert {
+    /// Minutes before the check to alert
+    pub minutes_before: i64,
+    /// Chime or toast
+    pub kind: PreAlertKind,
+    /// Sound file for a chime; a built-in tone is used when unset.
+    ///
+    /// If relative, it is resolved relative to the executable's directory.
+    #[serde(default)]
+    pub sound: Option<PathBuf>,
+    /// Text for a toast; defaults to a countdown like "Half hour check in 1 min"
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// What to do with a check that was skipped over (sleep, suspend, lag or a late start)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MissedCheckPolicy {
+    /// Activate the check late if it's recent enough, otherwise log it
+    #[default]
+    Activate,
+    /// Only log the missed check
+    Log,
+}
+
+/// Settings for handling missed checks
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct MissedChecks {
+    /// Whether missed checks are activated late or only logged
+    pub policy: MissedCheckPolicy,
+    /// How old a missed check may be and still be activated late.
+    ///
+    /// Also how far back to look when the app starts.
+    pub max_late_minutes: i64,
+}
+
+impl Default for MissedChecks {
+    fn default() -> Self {
+        Self {
+            policy: MissedCheckPolicy::Activate,
+            max_late_minutes: 10,
+        }
+    }
+}
+
+/// Second window for a public screen, without the mentor's controls
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PublicDisplay {
+    /// Open the public display window
+    pub enabled: bool,
+    /// Top-left corner of the window in desktop coordinates, to put it on another monitor
+    pub position: Option<[f32; 2]>,
+    /// Fill the monitor the window is on
+    pub fullscreen: bool,
+    /// Multiplier on the window's automatic text scaling
+    pub scale: f32,
+}
+
+impl Default for PublicDisplay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            position: None,
+            fullscreen: true,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Window behavior while a reminder is Active
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ActiveWindow {
+    /// Unminimize, raise and focus the window when a reminder activates
+    pub raise: bool,
+    /// Keep the window above others until the reminder is acknowledged
+    pub always_on_top: bool,
+}
+
+impl Default for ActiveWindow {
+    fn default() -> Self {
+        Self {
+            raise: true,
+            always_on_top: false,
+        }
+    }
+}
+
+/// When an Active reminder flashes the screen border
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FlashAlerts {
+    /// Never flash
+    Never,
+    /// Flash only while audio is unavailable, so the reminder isn't silent
+    #[default]
+    WithoutAudio,
+    /// Always flash, e.g. for a mentor who is hard of hearing
+    Always,
+}
+
+/// Accessibility settings
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Stop the breathing background and pulsing heading, and repaint less often
+    pub reduced_motion: bool,
+    /// Solid background with all text meeting WCAG AAA contrast (7:1)
+    pub high_contrast: bool,
+    /// When to flash the screen border for an Active reminder
+    pub flash: FlashAlerts,
 }
 
 impl Config {
-    /// Loads configuration from config.json and discovers audio files from songs_dir
-    pub fn load() -> Option<Self> {
-        let exe_dir: PathBuf = std::env::current_exe()
+    /// Returns the path of config.json, next to the executable
+    pub fn path() -> PathBuf {
+        Self::exe_dir().join("config.json")
+    }
+
+    fn exe_dir() -> PathBuf {
+        std::env::current_exe()
             .expect("Failed to get executable path")
             .parent()
             .expect("Executable must live in a directory")
-            .to_path_buf();
+            .to_path_buf()
+    }
 
-        let path = exe_dir.join("config.json");
+    /// Loads configuration from config.json and discovers audio files from songs_dir
+    pub fn load() -> Result<Self, String> {
+        let exe_dir = Self::exe_dir();
+        let path = Self::path();
 
-        let raw = fs::read_to_string(&path).unwrap();
+        let raw =
+            fs::read_to_string(&path).map_err(|e| format!("Can't read {}: {e}", path.display()))?;
 
-        let mut config: Config =
-            serde_json::from_str(&raw).expect("Invalid JSON in config.json");
+        let mut config: Config = serde_json::from_str(&raw)
+            .map_err(|e| format!("Invalid JSON in {}: {e}", path.display()))?;
+        config.config_path = path;
 
         // Resolve songs_dir:
         // - if missing/empty => default to <exe_dir>/songs
@@ -52,7 +387,191 @@ impl Config {
 
         config.songs = Self::load_songs_from(&config.songs_dir);
 
-        Some(config)
+        let paths = config
+            .pre_alerts
+            .iter_mut()
+            .filter_map(|alert| alert.sound.as_mut())
+            .chain(
+                config
+                    .announcements
+                    .iter_mut()
+                    .filter_map(|a| a.sound.as_mut()),
+            )
+            .chain(config.messages_file.as_mut());
+        for path in paths {
+            if path.is_relative() {
+                *path = exe_dir.join(&*path);
+            }
+        }
+
+        if config.check_types.is_empty() {
+            config.check_types = Self::default_check_types(&config);
+        }
+
+        if config.lab_hours.open >= config.lab_hours.close {
+            return Err(format!(
+                "lab_hours opens at {} but closes at {}; it must open first",
+                config.lab_hours.open.format("%H:%M"),
+                config.lab_hours.close.format("%H:%M")
+            ));
+        }
+
+        // Checks are told apart by id, so two with the same id would be mixed up.
+        let mut ids = HashSet::new();
+        if let Some(check) = config
+            .check_types
+            .iter()
+            .find(|c| !ids.insert(c.id.as_str()))
+        {
+            return Err(format!(
+                "Duplicate check id \"{}\" in check_types",
+                check.id
+            ));
+        }
+
+        if config.pending_minutes < 0 {
+            tracing::warn!(
+                "pending_minutes is {}; showing no countdown",
+                config.pending_minutes
+            );
+            config.pending_minutes = 0;
+        }
+        if config.snooze_minutes < 1 {
+            tracing::warn!(
+                "snooze_minutes is {}; snoozing for 1 minute",
+                config.snooze_minutes
+            );
+            config.snooze_minutes = 1;
+        }
+        // A pre-alert goes off during the minute ending `minutes_before` ahead of
+        // the check, so anything under 1 would fall on or after the check itself.
+        config.pre_alerts.retain(|alert| {
+            let valid = alert.minutes_before >= 1;
+            if !valid {
+                tracing::warn!(
+                    "Ignoring pre-alert with minutes_before {}: it must be at least 1",
+                    alert.minutes_before
+                );
+            }
+            valid
+        });
+
+        if let Some(Err(e)) = config.browser.as_ref().map(BrowserCommand::args) {
+            tracing::warn!("Invalid browser command: {e}; form links will fail to open");
+        }
+
+        // Announcements only go off while the lab is open, so others would never show.
+        let schedule = config.schedule();
+        config.announcements.retain(|a| {
+            let open = a.local_time().is_some_and(|at| schedule.is_open(at));
+            if !open {
+                tracing::warn!(
+                    "Ignoring announcement \"{}\" at {}: the lab is closed then",
+                    a.message,
+                    a.at.format("%Y-%m-%d %H:%M")
+                );
+            }
+            open
+        });
+
+        for check in &mut config.check_types {
+            check.form_url = check.form_url.as_deref().and_then(Self::link);
+
+            if let Some(color) = &check.color
+                && Color32::from_hex(color).is_err()
+            {
+                tracing::warn!(
+                    "Invalid color \"{color}\" for check \"{}\"; using the theme's",
+                    check.id
+                );
+                check.color = None;
+            }
+
+            if let Some(sounds) = check.sounds.take() {
+                let sounds = Self::resolve_songs_dir(&exe_dir, &sounds);
+                check.songs = Self::load_songs_from(&sounds);
+                check.sounds = Some(sounds);
+            }
+        }
+
+        // Roster entries with their own song source get their songs resolved
+        // the same way; entries without one fall back to the shared songs.
+        for entry in &mut config.roster {
+            if let Some(dir) = entry.songs_dir.take() {
+                let dir = Self::resolve_songs_dir(&exe_dir, &dir);
+                entry.songs = Self::load_songs_from(&dir);
+                entry.songs_dir = Some(dir);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// The standard hourly (:55) and half hour (:30) checks
+    fn default_check_types(config: &Config) -> Vec<CheckDefinition> {
+        vec![
+            CheckDefinition {
+                id: "hour".to_string(),
+                label: "One hour check".to_string(),
+                form_url: Self::link(&config.hourly_link),
+                triggers: vec![Trigger::Hourly(55)],
+                color: None,
+                sounds: None,
+                songs: Vec::new(),
+            },
+            CheckDefinition {
+                id: "half_hour".to_string(),
+                label: "Half hour check".to_string(),
+                form_url: Self::link(&config.thirty_link),
+                triggers: vec![Trigger::Hourly(30)],
+                color: None,
+                sounds: None,
+                songs: Vec::new(),
+            },
+        ]
+    }
+
+    /// Treats an unset (empty) link as no form at all
+    fn link(url: &str) -> Option<String> {
+        let url = url.trim();
+        (!url.is_empty()).then(|| url.to_string())
+    }
+
+    /// Builds the check schedule from lab hours, closures and check types
+    pub fn schedule(&self) -> Schedule {
+        Schedule::new(
+            self.lab_hours,
+            self.closures.clone(),
+            self.check_types
+                .iter()
+                .cloned()
+                .map(CheckType::new)
+                .collect(),
+        )
+    }
+
+    /// Returns the roster entry for the mentor on duty at the given moment
+    pub fn mentor_on_duty(&self, now: DateTime<Local>) -> Option<&RosterEntry> {
+        roster::on_duty(&self.roster, now)
+    }
+
+    /// Returns the songs to play at the given moment.
+    ///
+    /// Uses the on-duty mentor's songs when they have any, otherwise the shared songs.
+    pub fn songs_for(&self, now: DateTime<Local>) -> &[PathBuf] {
+        match self.mentor_on_duty(now) {
+            Some(entry) if !entry.songs.is_empty() => &entry.songs,
+            _ => &self.songs,
+        }
+    }
+
+    /// Returns the idle screen message for the given moment.
+    ///
+    /// Uses the on-duty mentor's greeting when set, otherwise `mentor_text`.
+    pub fn greeting_for(&self, now: DateTime<Local>) -> &str {
+        self.mentor_on_duty(now)
+            .and_then(|entry| entry.greeting.as_deref())
+            .unwrap_or(&self.mentor_text)
     }
 
     fn resolve_songs_dir(config_dir: &Path, configured: &Path) -> PathBuf {
@@ -69,42 +588,217 @@ impl Config {
         fs::canonicalize(&resolved).unwrap_or(resolved)
     }
 
-    /// Scans a folder for supported audio files (.mp3, .wav, .ogg, .flac)
-    fn load_songs_from(dir: &Path) -> Vec<PathBuf> {
+    /// Collects supported audio files (.mp3, .wav, .ogg, .flac) from a song source.
+    ///
+    /// Folders are scanned recursively and extensions are matched
+    /// case-insensitively. M3U/PLS playlists, whether found inside the folder
+    /// or given directly as the source, contribute the audio files they list.
+    pub fn load_songs_from(source: &Path) -> Vec<PathBuf> {
+        let mut songs = Vec::new();
+
+        if source.is_dir() {
+            Self::scan_dir(source, &mut songs, &mut HashSet::new());
+        } else if playlist::is_playlist(source) {
+            songs.extend(
+                playlist::read_playlist(source)
+                    .into_iter()
+                    .filter(|p| Self::is_audio_file(p) && p.is_file()),
+            );
+        }
+
+        songs.sort();
+        songs.dedup();
+        songs
+    }
+
+    /// Walks a folder and its subfolders, collecting audio files and playlist entries.
+    ///
+    /// `visited` holds canonical folder paths so symlink loops are only walked once.
+    fn scan_dir(dir: &Path, songs: &mut Vec<PathBuf>, visited: &mut HashSet<PathBuf>) {
+        if !visited.insert(fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf())) {
+            return;
+        }
+
         let entries = match fs::read_dir(dir) {
             Ok(entries) => entries,
-            Err(_) => return Vec::new(), // folder missing/unreadable -> no sounds
+            Err(_) => return, // folder missing/unreadable -> no sounds
         };
 
-        entries
-            .filter_map(|e| e.ok())
-            .map(|e| e.path())
-            .filter(|p| {
-                matches!(
-                    p.extension().and_then(|e| e.to_str()),
-                    Some("mp3" | "wav" | "ogg" | "flac")
-                )
-            })
-            .collect()
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_dir() {
+                Self::scan_dir(&path, songs, visited);
+            } else if Self::is_audio_file(&path) {
+                songs.push(path);
+            } else if playlist::is_playlist(&path) {
+                songs.extend(
+                    playlist::read_playlist(&path)
+                        .into_iter()
+                        .filter(|p| Self::is_audio_file(p) && p.is_file()),
+                );
+            }
+        }
+    }
+
+    /// Returns true if the path has a supported audio extension, ignoring case
+    fn is_audio_file(path: &Path) -> bool {
+        matches!(
+            playlist::lowercase_extension(path).as_deref(),
+            Some("mp3" | "wav" | "ogg" | "flac")
+        )
     }
 
     /// Opens the configured songs folder in the OS file explorer.
     pub fn open_songs_folder(&self) {
-        let dir = &self.songs_dir;
+        Self::open_with_system(&self.songs_dir, "songs folder");
+    }
+
+    /// Opens config.json in the system's default editor
+    pub fn open_config_file(&self) {
+        Self::open_with_system(&self.config_path, "config file");
+    }
+
+    /// Fills in the placeholders of a check's form link for the check at `at`.
+    ///
+    /// `{mentor}`, `{check}`, `{date}` and `{time}` become the mentor on duty,
+    /// the check's label and the check's date and time, URL-encoded so they can
+    /// pre-fill form fields.
+    pub fn form_link(&self, url: &str, check: &CheckType, at: DateTime<Local>) -> String {
+        let mentor = self
+            .mentor_on_duty(at)
+            .map(|entry| entry.mentor.as_str())
+            .unwrap_or_default();
+        let values = [
+            ("{mentor}", mentor.to_string()),
+            ("{check}", check.label.clone()),
+            ("{date}", at.format("%Y-%m-%d").to_string()),
+            ("{time}", at.format("%H:%M").to_string()),
+        ];
+
+        values
+            .iter()
+            .fold(url.to_string(), |link, (placeholder, value)| {
+                link.replace(
+                    placeholder,
+                    &utf8_percent_encode(value, NON_ALPHANUMERIC).to_string(),
+                )
+            })
+    }
 
+    /// Opens a link in the configured browser command or the default browser
+    pub fn open_in_browser(&self, url: &str) -> io::Result<()> {
+        let Some(command) = &self.browser else {
+            return webbrowser::open(url);
+        };
+
+        let args = command
+            .args()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let (program, rest) = args.split_first().expect("browser command has a program");
+        let mut rest: Vec<String> = rest.iter().map(|arg| arg.replace("{url}", url)).collect();
+        if !args.iter().any(|arg| arg.contains("{url}")) {
+            rest.push(url.to_string());
+        }
+
+        Command::new(program)
+            .args(rest)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| io::Error::new(e.kind(), format!("can't run `{program}`: {e}")))
+    }
+
+    /// Opens a file or folder with the operating system's default handler
+    fn open_with_system(path: &Path, what: &str) {
         let spawn_result = if cfg!(target_os = "windows") {
-            Command::new("explorer").arg(dir).spawn()
+            Command::new("explorer").arg(path).spawn()
         } else if cfg!(target_os = "macos") {
-            Command::new("open").arg(dir).spawn()
+            Command::new("open").arg(path).spawn()
         } else if cfg!(target_os = "linux") {
-            Command::new("xdg-open").arg(dir).spawn()
+            Command::new("xdg-open").arg(path).spawn()
         } else {
-            println!("Unsupported operating system for opening file explorer automatically.");
+            tracing::warn!("Unsupported operating system for opening the {what} automatically");
             return;
         };
 
         if let Err(e) = spawn_result {
-            eprintln!("Failed to open songs folder: {e}");
+            tracing::error!(path = %path.display(), "Failed to open {what}: {e}");
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    fn args(line: &str) -> Result<Vec<String>, String> {
+        BrowserCommand::Line(line.to_string()).args()
+    }
+
+    #[test]
+    fn splits_browser_command_on_spaces() {
+        assert_eq!(
+            args("  firefox  --new-window {url} ").unwrap(),
+            ["firefox", "--new-window", "{url}"]
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_browser_arguments_together() {
+        assert_eq!(
+            args(r#""C:\Program Files\Mozilla Firefox\firefox.exe" -url {url}"#).unwrap(),
+            [
+                r"C:\Program Files\Mozilla Firefox\firefox.exe",
+                "-url",
+                "{url}"
+            ]
+        );
+        assert_eq!(
+            args("chromium '--user-data-dir=/tmp/lab profile' ''").unwrap(),
+            ["chromium", "--user-data-dir=/tmp/lab profile", ""]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_browser_commands() {
+        assert!(args(r#""C:\Program Files\firefox.exe -url"#).is_err());
+        assert!(args("   ").is_err());
+        assert!(BrowserCommand::Args(Vec::new()).args().is_err());
+    }
+
+    #[test]
+    fn form_link_fills_in_encoded_placeholders() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "mentor_text": "Hi",
+                "roster": [{ "mentor": "Ada Lovelace & Co", "start": "10:00", "end": "18:00" }]
+            }"#,
+        )
+        .unwrap();
+        let check = test_util::check("half_hour", "Half hour check", &[]);
+        let at = |hour| test_util::local((2026, 6, 1), hour, 30);
+        let url = "https://forms.example/view?m={mentor}&c={check}&d={date}&t={time}&x=%7B";
+
+        assert_eq!(
+            config.form_link(url, &check, at(14)),
+            "https://forms.example/view?m=Ada%20Lovelace%20%26%20Co&c=Half%20hour%20check\
+             &d=2026%2D06%2D01&t=14%3A30&x=%7B"
+        );
+        // Nobody is on duty before 10:00.
+        assert!(
+            config
+                .form_link(url, &check, at(9))
+                .starts_with("https://forms.example/view?m=&c=")
+        );
+        assert_eq!(
+            config.form_link("https://forms.example/", &check, at(14)),
+            "https://forms.example/"
+        );
+    }
+
+    #[test]
+    fn browser_command_accepts_string_or_list() {
+        let line: BrowserCommand = serde_json::from_str(r#""firefox {url}""#).unwrap();
+        let list: BrowserCommand = serde_json::from_str(r#"["firefox", "{url}"]"#).unwrap();
+        assert_eq!(line.args(), list.args());
+    }
+}
@@ -0,0 +1,276 @@
+//! Keyboard shortcuts
+//!
+//! Maps configurable key combinations (e.g. "Ctrl+Shift+A") to reminder
+//! actions and lists them in a help overlay.
+
+use eframe::egui::{self, Context, Key, KeyboardShortcut, Modifiers};
+use serde::Deserialize;
+
+/// Something a shortcut can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Acknowledge,
+    OpenForm,
+    Pause,
+    Snooze,
+    SkipTrack,
+    /// Forces the first check to go off, for testing
+    DebugTrigger,
+    Help,
+}
+
+impl KeyAction {
+    /// Text shown in the help overlay
+    fn description(self) -> &'static str {
+        match self {
+            KeyAction::Acknowledge => "Mark the check done",
+            KeyAction::OpenForm => "Open the check's form",
+            KeyAction::Pause => "Pause or resume music",
+            KeyAction::Snooze => "Snooze the reminder",
+            KeyAction::SkipTrack => "Skip to the next song",
+            KeyAction::DebugTrigger => "Start a check now (debug)",
+            KeyAction::Help => "Show or hide this help",
+        }
+    }
+}
+
+/// Key bindings from config.json, written like "S", "Ctrl+Enter" or "Ctrl+Shift+A".
+///
+/// An empty string leaves the action unbound. The defaults all need Ctrl so
+/// someone typing near the lab machine doesn't set them off by accident.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct KeymapConfig {
+    pub acknowledge: String,
+    pub open_form: String,
+    pub pause: String,
+    pub snooze: String,
+    pub skip_track: String,
+    /// Only active in debug mode
+    pub debug_trigger: String,
+    pub help: String,
+}
+
+impl Default for KeymapConfig {
+    fn default() -> Self {
+        Self {
+            acknowledge: "Ctrl+Enter".to_string(),
+            open_form: "Ctrl+O".to_string(),
+            pause: "Ctrl+P".to_string(),
+            snooze: "Ctrl+S".to_string(),
+            skip_track: "Ctrl+N".to_string(),
+            debug_trigger: "Ctrl+Shift+A".to_string(),
+            help: "F1".to_string(),
+        }
+    }
+}
+
+/// Parsed key bindings
+pub struct Keymap {
+    bindings: Vec<(KeyAction, KeyboardShortcut)>,
+}
+
+impl Keymap {
+    /// Parses the configured bindings; the debug trigger is only bound in debug mode
+    pub fn new(config: &KeymapConfig, debug: bool) -> Self {
+        let mut entries = vec![
+            (KeyAction::Acknowledge, &config.acknowledge),
+            (KeyAction::OpenForm, &config.open_form),
+            (KeyAction::Pause, &config.pause),
+            (KeyAction::Snooze, &config.snooze),
+            (KeyAction::SkipTrack, &config.skip_track),
+            (KeyAction::Help, &config.help),
+        ];
+        if debug {
+            entries.push((KeyAction::DebugTrigger, &config.debug_trigger));
+        }
+
+        let bindings = entries
+            .into_iter()
+            .filter(|(_, binding)| !binding.trim().is_empty())
+            .filter_map(|(action, binding)| match Self::parse(binding) {
+                Some(shortcut) => Some((action, shortcut)),
+                None => {
+                    tracing::warn!(
+                        "Invalid key binding \"{binding}\" for {action:?}; leaving unbound"
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        Self { bindings }
+    }
+
+    /// Parses a binding like "Ctrl+Shift+A" into a shortcut
+    fn parse(binding: &str) -> Option<KeyboardShortcut> {
+        let mut parts: Vec<&str> = binding.split('+').map(str::trim).collect();
+        let key = Key::from_name(parts.pop()?)?;
+
+        let mut modifiers = Modifiers::NONE;
+        for part in parts {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CTRL,
+                "shift" => Modifiers::SHIFT,
+                "alt" => Modifiers::ALT,
+                "cmd" | "command" => Modifiers::COMMAND,
+                _ => return None,
+            };
+        }
+
+        Some(KeyboardShortcut::new(modifiers, key))
+    }
+
+    /// Returns the actions whose shortcuts were pressed this frame.
+    ///
+    /// Nothing fires while a text field has focus, so typing doesn't trigger actions.
+    pub fn pressed(&self, ctx: &Context) -> Vec<KeyAction> {
+        if ctx.memory(|m| m.focused().is_some()) {
+            return Vec::new();
+        }
+
+        // Shortcuts with more modifiers go first so "Ctrl+S" isn't taken by "S".
+        let mut bindings: Vec<_> = self.bindings.iter().collect();
+        bindings.sort_by_key(|(_, shortcut)| std::cmp::Reverse(modifier_count(shortcut.modifiers)));
+
+        ctx.input_mut(|input| {
+            bindings
+                .into_iter()
+                .filter(|(_, shortcut)| input.consume_shortcut(shortcut))
+                .map(|(action, _)| *action)
+                .collect()
+        })
+    }
+
+    /// Returns the shortcut text for an action, e.g. "F1", if it's bound
+    pub fn label(&self, ctx: &Context, action: KeyAction) -> Option<String> {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, shortcut)| ctx.format_shortcut(shortcut))
+    }
+
+    /// Draws the help overlay listing every binding; clears `open` when dismissed
+    pub fn show_help(&self, ctx: &Context, open: &mut bool) {
+        let modal = egui::Modal::new(egui::Id::new("keymap_help")).show(ctx, |ui| {
+            ui.heading("Keyboard shortcuts");
+            ui.add_space(10.0);
+
+            egui::Grid::new("keymap_help_grid")
+                .num_columns(2)
+                .spacing([24.0, 6.0])
+                .show(ui, |ui| {
+                    for (action, shortcut) in &self.bindings {
+                        ui.strong(ctx.format_shortcut(shortcut));
+                        ui.label(action.description());
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(10.0);
+            ui.button("Close").clicked()
+        });
+
+        if modal.inner || modal.should_close() {
+            *open = false;
+        }
+    }
+}
+
+fn modifier_count(modifiers: Modifiers) -> usize {
+    [
+        modifiers.ctrl,
+        modifiers.shift,
+        modifiers.alt,
+        modifiers.command,
+    ]
+    .into_iter()
+    .filter(|&held| held)
+    .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eframe::egui::{Event, RawInput};
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(
+            Keymap::parse("Ctrl+Shift+A"),
+            Some(KeyboardShortcut::new(
+                Modifiers::CTRL | Modifiers::SHIFT,
+                Key::A
+            ))
+        );
+        assert_eq!(
+            Keymap::parse("control + Enter"),
+            Some(KeyboardShortcut::new(Modifiers::CTRL, Key::Enter))
+        );
+        assert_eq!(
+            Keymap::parse("F1"),
+            Some(KeyboardShortcut::new(Modifiers::NONE, Key::F1))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_bindings() {
+        assert_eq!(Keymap::parse("Ctrl+Banana"), None);
+        assert_eq!(Keymap::parse("Hyper+A"), None);
+        assert_eq!(Keymap::parse(""), None);
+    }
+
+    #[test]
+    fn empty_or_invalid_bindings_are_unbound() {
+        let config = KeymapConfig {
+            snooze: String::new(),
+            pause: "Ctrl+Banana".to_string(),
+            ..Default::default()
+        };
+        let keymap = Keymap::new(&config, false);
+        let ctx = Context::default();
+
+        assert_eq!(keymap.label(&ctx, KeyAction::Snooze), None);
+        assert_eq!(keymap.label(&ctx, KeyAction::Pause), None);
+        assert_eq!(keymap.label(&ctx, KeyAction::DebugTrigger), None);
+        assert!(keymap.label(&ctx, KeyAction::Help).is_some());
+    }
+
+    /// Returns the actions fired by pressing `key` with `modifiers` for one frame
+    fn press(keymap: &Keymap, modifiers: Modifiers, key: Key) -> Vec<KeyAction> {
+        let ctx = Context::default();
+        let input = RawInput {
+            modifiers,
+            events: vec![Event::Key {
+                key,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers,
+            }],
+            ..Default::default()
+        };
+        let mut pressed = Vec::new();
+        let _ = ctx.run(input, |ctx| pressed = keymap.pressed(ctx));
+        pressed
+    }
+
+    #[test]
+    fn shortcut_with_more_modifiers_wins() {
+        let config = KeymapConfig {
+            snooze: "Ctrl+S".to_string(),
+            skip_track: "Ctrl+Shift+S".to_string(),
+            ..Default::default()
+        };
+        let keymap = Keymap::new(&config, false);
+
+        assert_eq!(
+            press(&keymap, Modifiers::CTRL | Modifiers::SHIFT, Key::S),
+            vec![KeyAction::SkipTrack]
+        );
+        assert_eq!(
+            press(&keymap, Modifiers::CTRL, Key::S),
+            vec![KeyAction::Snooze]
+        );
+    }
+}
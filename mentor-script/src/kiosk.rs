@@ -0,0 +1,151 @@
+//! Kiosk mode for the lab display
+//!
+//! Keeps the window fullscreen and open, hides an idle cursor, and puts
+//! exiting behind an unlock key and optional PIN.
+
+use crate::config::Config;
+use eframe::egui::{self, Context, CursorIcon, Key, ViewportCommand};
+use serde::Deserialize;
+
+/// Key used when `unlock_key` isn't a key egui knows
+const DEFAULT_UNLOCK_KEY: Key = Key::F12;
+
+/// Kiosk settings from config.json
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct KioskConfig {
+    /// Run fullscreen without decorations and ignore window close requests
+    pub enabled: bool,
+    /// Key that opens the unlock dialog (e.g. "F12")
+    pub unlock_key: String,
+    /// PIN asked for before exiting or opening the config; no PIN when unset
+    pub pin: Option<String>,
+    /// Seconds without mouse movement before the cursor is hidden
+    pub hide_cursor_seconds: f32,
+}
+
+impl Default for KioskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            unlock_key: "F12".to_string(),
+            pin: None,
+            hide_cursor_seconds: 5.0,
+        }
+    }
+}
+
+/// Unlock dialog and window guards for kiosk mode
+pub struct KioskLock {
+    unlock_key: Key,
+    pin: Option<String>,
+    hide_cursor_seconds: f32,
+    dialog_open: bool,
+    /// PIN was entered correctly for the open dialog
+    unlocked: bool,
+    pin_input: String,
+    wrong_pin: bool,
+    /// Set once the user chose to exit, so the close isn't cancelled
+    exiting: bool,
+}
+
+impl KioskLock {
+    pub fn new(config: &KioskConfig) -> Self {
+        let unlock_key = Key::from_name(&config.unlock_key).unwrap_or_else(|| {
+            tracing::warn!(
+                "Unknown kiosk unlock key \"{}\"; using {}",
+                config.unlock_key,
+                DEFAULT_UNLOCK_KEY.name()
+            );
+            DEFAULT_UNLOCK_KEY
+        });
+
+        Self {
+            unlock_key,
+            pin: config.pin.clone().filter(|pin| !pin.is_empty()),
+            hide_cursor_seconds: config.hide_cursor_seconds,
+            dialog_open: false,
+            unlocked: false,
+            pin_input: String::new(),
+            wrong_pin: false,
+            exiting: false,
+        }
+    }
+
+    /// Returns true unless the unlock dialog is open and unlocked
+    pub fn is_locked(&self) -> bool {
+        !(self.dialog_open && self.unlocked)
+    }
+
+    /// Blocks window close requests, hides an idle cursor and draws the unlock dialog
+    pub fn update(&mut self, ctx: &Context, config: &Config) {
+        if ctx.input(|i| i.viewport().close_requested()) && !self.exiting {
+            ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+        }
+
+        if ctx.input(|i| i.key_pressed(self.unlock_key)) && !self.dialog_open {
+            self.dialog_open = true;
+            self.unlocked = self.pin.is_none();
+            self.pin_input.clear();
+            self.wrong_pin = false;
+        }
+
+        if !self.dialog_open {
+            if ctx.input(|i| i.pointer.time_since_last_movement()) >= self.hide_cursor_seconds {
+                ctx.set_cursor_icon(CursorIcon::None);
+            }
+            return;
+        }
+
+        let modal = egui::Modal::new(egui::Id::new("kiosk_unlock")).show(ctx, |ui| {
+            ui.set_width(280.0);
+            ui.heading("Kiosk mode");
+            ui.add_space(10.0);
+
+            if !self.unlocked {
+                let input = ui.add(
+                    egui::TextEdit::singleline(&mut self.pin_input)
+                        .password(true)
+                        .hint_text("PIN"),
+                );
+                input.request_focus();
+
+                if self.wrong_pin {
+                    ui.colored_label(ui.visuals().error_fg_color, "Wrong PIN");
+                }
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                    if ui.button("Unlock").clicked() || submitted {
+                        self.unlocked = self.pin.as_deref() == Some(self.pin_input.as_str());
+                        self.wrong_pin = !self.unlocked;
+                        self.pin_input.clear();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.dialog_open = false;
+                    }
+                });
+                return;
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("Exit Mentor Script").clicked() {
+                    self.exiting = true;
+                    ui.ctx().send_viewport_cmd(ViewportCommand::Close);
+                }
+                if ui.button("Open config").clicked() {
+                    config.open_config_file();
+                    self.dialog_open = false;
+                }
+                if ui.button("Cancel").clicked() {
+                    self.dialog_open = false;
+                }
+            });
+        });
+
+        if modal.should_close() {
+            self.dialog_open = false;
+        }
+    }
+}
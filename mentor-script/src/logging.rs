@@ -0,0 +1,134 @@
+//! Logging setup
+//!
+//! Sends `tracing` events to stderr and to a log file in the state directory
+//! that rotates daily, at the level set in config.json.
+
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Logging settings from config.json
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Level or filter, e.g. "info" or "warn,mentor_script=debug".
+    ///
+    /// The `RUST_LOG` environment variable overrides it.
+    pub level: String,
+    /// Folder for log files; defaults to the platform's state directory
+    pub dir: Option<PathBuf>,
+    /// How many daily log files to keep
+    pub keep_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            dir: None,
+            keep_files: 7,
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Reads only the `logging` section of a config file, so logging can
+    /// start before the rest of the config is loaded and checked.
+    ///
+    /// A missing or unreadable file gives the defaults, since loading the
+    /// config reports it anyway; a section that doesn't parse is an error.
+    pub fn read(config_path: &Path) -> Result<Self, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct Section {
+            #[serde(default)]
+            logging: LoggingConfig,
+        }
+
+        match fs::read_to_string(config_path) {
+            Ok(raw) => serde_json::from_str::<Section>(&raw).map(|section| section.logging),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+/// Installs the global logger writing to stderr and the log file, using the
+/// `logging` section of the config file at `config_path`.
+///
+/// If the section is invalid the defaults are used; if the log folder can't
+/// be created, logs only go to stderr. Both are logged as warnings.
+pub fn init(config_path: &Path) {
+    let (config, config_error) = match LoggingConfig::read(config_path) {
+        Ok(config) => (config, None),
+        Err(e) => (LoggingConfig::default(), Some(e)),
+    };
+
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&config.level))
+        .unwrap_or_else(|e| {
+            eprintln!("Invalid log level \"{}\": {e}; using info", config.level);
+            EnvFilter::new("info")
+        });
+
+    let dir = config.dir.clone().unwrap_or_else(state_dir);
+    let file = fs::create_dir_all(&dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| {
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix("mentor-script")
+                .filename_suffix("log")
+                .max_log_files(config.keep_files.max(1))
+                .build(&dir)
+                .map_err(|e| e.to_string())
+        });
+
+    let (file_layer, file_error) = match file {
+        Ok(appender) => (
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(appender),
+            ),
+            None,
+        ),
+        Err(e) => (None, Some(e)),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .init();
+
+    match file_error {
+        None => tracing::info!(dir = %dir.display(), "Logging to file"),
+        Some(e) => tracing::warn!(dir = %dir.display(), "Can't write log files: {e}"),
+    }
+    if let Some(e) = config_error {
+        tracing::warn!(
+            "Invalid logging settings in {}: {e}; using defaults",
+            config_path.display()
+        );
+    }
+}
+
+/// Returns the folder for log files and other state kept between runs.
+///
+/// `$XDG_STATE_HOME/mentor-script` (or `~/.local/state/mentor-script`) on Linux
+/// and macOS, `%LOCALAPPDATA%\mentor-script` on Windows, otherwise `./logs`.
+pub fn state_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/state")))
+    };
+
+    base.map(|dir| dir.join("mentor-script"))
+        .unwrap_or_else(|| PathBuf::from("logs"))
+}
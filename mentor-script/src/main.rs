@@ -1,29 +1,75 @@
 //! Mentor Script
 //!
-//! This application displays an always-on-top GUI window that reminds users
-//! to check in at regular intervals (:30 and :55 of each hour).
+//! This application displays a GUI window that reminds users to check in at
+//! regular intervals (:30 and :55 of each hour), raising itself to the front
+//! when a check is due.
 
-use eframe::egui::ViewportBuilder;
-use eframe::HardwareAcceleration;
 use crate::app::MentorApp;
 use crate::config::Config;
+use crate::sound::Audio;
+use chrono::Local;
+use eframe::HardwareAcceleration;
+use eframe::egui::ViewportBuilder;
 
-mod scheduler;
-mod config;
+mod announcement;
 mod app;
+mod clock;
+mod config;
+mod keymap;
+mod kiosk;
+mod logging;
+mod message_board;
+mod notifications;
+mod playlist;
+mod qr;
+mod roster;
+mod scheduler;
 mod sound;
+#[cfg(test)]
+mod test_util;
+mod theme;
+mod tray;
 
 fn main() -> ! {
-    let config = Config::load().expect("Failed to load config");
+    if std::env::args().any(|arg| arg == "--list-audio-devices") {
+        for name in Audio::output_device_names() {
+            println!("{name}");
+        }
+        std::process::exit(0);
+    }
+
+    logging::init(&Config::path());
 
-    eprintln!("Starting Mentor Script GUI!");
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!("Failed to load config: {e}");
+            std::process::exit(1);
+        }
+    };
+    if std::env::args().any(|arg| arg == "--kiosk") {
+        config.kiosk.enabled = true;
+    }
+    if std::env::args().any(|arg| arg == "--debug") {
+        config.debug = true;
+    }
+
+    if std::env::args().any(|arg| arg == "--next-checks") {
+        print_next_checks(&config);
+        std::process::exit(0);
+    }
+
+    tracing::info!("Starting Mentor Script GUI");
+
+    let kiosk = config.kiosk.enabled;
 
     let options = eframe::NativeOptions {
         viewport: ViewportBuilder::default()
             .with_title("Mentor Script")
             .with_maximized(true)
-            .with_resizable(true)
-            .with_decorations(true),
+            .with_fullscreen(kiosk)
+            .with_resizable(!kiosk)
+            .with_decorations(!kiosk),
         vsync: true,
         multisampling: 0,
         depth_buffer: 0,
@@ -43,12 +89,30 @@ fn main() -> ! {
     if let Err(e) = eframe::run_native(
         "Mentor Script",
         options,
-        Box::new(|_cc| Ok(Box::new(MentorApp::new(config)))),
+        Box::new(|cc| Ok(Box::new(MentorApp::new(config, &cc.egui_ctx)))),
     ) {
-        eprintln!("eframe failed: {e}");
+        tracing::error!("eframe failed: {e}");
     }
 
-    eprintln!("Mentor Script Stopped!");
+    tracing::info!("Mentor Script stopped");
 
     std::process::exit(0);
-}
\ No newline at end of file
+}
+
+/// Prints the next few checks and how long until each one
+fn print_next_checks(config: &Config) {
+    let now = Local::now();
+    let schedule = config.schedule();
+
+    for (at, check) in schedule.upcoming(now).take(10) {
+        let secs = (at - now).num_seconds();
+        println!(
+            "{}  {:<24} in {}:{:02}:{:02}",
+            at.format("%a %H:%M"),
+            check.to_string(),
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        );
+    }
+}
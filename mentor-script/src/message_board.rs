@@ -0,0 +1,233 @@
+//! Rotating idle-screen message board
+//!
+//! Cycles the idle screen through the greeting and a list of signage messages
+//! (tutoring subjects, upcoming events, lab rules) from config or a local file.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Weekday};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long a message stays up when it doesn't set `seconds`
+const DEFAULT_SECONDS: u64 = 10;
+
+/// How often the messages file is checked for changes
+const FILE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A message shown on the idle screen
+#[derive(Debug, Deserialize, Clone)]
+pub struct BoardMessage {
+    /// Text to show
+    pub text: String,
+    /// How many seconds to show it before moving on
+    #[serde(default = "default_seconds")]
+    pub seconds: u64,
+    /// Text color as a hex string (e.g. "#23F123")
+    #[serde(default)]
+    pub color: Option<String>,
+
+    /// Weekdays to show it on. Empty means every day.
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Time of day it starts showing (e.g. "10:00")
+    #[serde(default)]
+    pub start: Option<NaiveTime>,
+    /// Time of day it stops showing, exclusive (e.g. "14:00")
+    #[serde(default)]
+    pub end: Option<NaiveTime>,
+    /// First date it shows (e.g. "2026-10-01")
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    /// Last date it shows, inclusive (e.g. "2026-10-31")
+    #[serde(default)]
+    pub until: Option<NaiveDate>,
+}
+
+fn default_seconds() -> u64 {
+    DEFAULT_SECONDS
+}
+
+impl BoardMessage {
+    /// Returns true if the message's day, time and date constraints allow showing it now
+    pub fn is_active(&self, now: DateTime<Local>) -> bool {
+        let time = now.time();
+        let date = now.date_naive();
+
+        (self.days.is_empty() || self.days.contains(&now.weekday()))
+            && self.start.is_none_or(|start| start <= time)
+            && self.end.is_none_or(|end| time < end)
+            && self.from.is_none_or(|from| from <= date)
+            && self.until.is_none_or(|until| date <= until)
+    }
+}
+
+/// Rotation through the greeting and the currently active messages
+pub struct MessageBoard {
+    messages: Vec<BoardMessage>,
+    file: Option<PathBuf>,
+    file_messages: Vec<BoardMessage>,
+    file_modified: Option<SystemTime>,
+    last_file_check: Option<Instant>,
+    /// Position in the rotation; 0 is the greeting, then each active message
+    slot: usize,
+    slot_since: Instant,
+}
+
+impl MessageBoard {
+    /// Creates a board from config messages and an optional JSON messages file
+    pub fn new(messages: Vec<BoardMessage>, file: Option<PathBuf>) -> Self {
+        Self {
+            messages,
+            file,
+            file_messages: Vec::new(),
+            file_modified: None,
+            last_file_check: None,
+            slot: 0,
+            slot_since: Instant::now(),
+        }
+    }
+
+    /// Returns the message to show now, or `None` when it's the greeting's turn.
+    ///
+    /// Advances the rotation once the current slot's time is up.
+    pub fn current(&mut self, now: DateTime<Local>) -> Option<BoardMessage> {
+        self.reload_file_if_changed();
+        self.current_at(now, Instant::now())
+    }
+
+    /// Does the work of `current`, with the real time as `instant`
+    fn current_at(&mut self, now: DateTime<Local>, instant: Instant) -> Option<BoardMessage> {
+        let active: Vec<&BoardMessage> = self
+            .messages
+            .iter()
+            .chain(&self.file_messages)
+            .filter(|m| m.is_active(now))
+            .collect();
+
+        if self.slot > active.len() {
+            self.slot = 0;
+        }
+
+        let slot_seconds = match self.slot {
+            0 => DEFAULT_SECONDS,
+            i => active[i - 1].seconds.max(1),
+        };
+        if instant.saturating_duration_since(self.slot_since) >= Duration::from_secs(slot_seconds) {
+            self.slot = (self.slot + 1) % (active.len() + 1);
+            self.slot_since = instant;
+        }
+
+        self.slot.checked_sub(1).map(|i| active[i].clone())
+    }
+
+    /// Re-reads the messages file when its modification time changes
+    fn reload_file_if_changed(&mut self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        if self
+            .last_file_check
+            .is_some_and(|at| at.elapsed() < FILE_CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.last_file_check = Some(Instant::now());
+
+        let modified = fs::metadata(file).and_then(|m| m.modified()).ok();
+        if modified == self.file_modified {
+            return;
+        }
+        self.file_modified = modified;
+
+        self.file_messages = match fs::read_to_string(file) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                tracing::warn!("Invalid JSON in {}: {e}", file.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(), // file missing/unreadable -> no extra messages
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::local as at;
+
+    const MONDAY: (i32, u32, u32) = (2026, 6, 1);
+
+    fn message(json: &str) -> BoardMessage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Texts shown at each of the given seconds after the board was created
+    fn shown(board: &mut MessageBoard, now: DateTime<Local>, seconds: &[u64]) -> Vec<String> {
+        let start = board.slot_since;
+        seconds
+            .iter()
+            .map(|&s| {
+                board
+                    .current_at(now, start + Duration::from_secs(s))
+                    .map_or("greeting".to_string(), |m| m.text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rotates_through_greeting_and_messages() {
+        let mut board = MessageBoard::new(
+            vec![
+                message(r#"{ "text": "Tutoring", "seconds": 3 }"#),
+                message(r#"{ "text": "Rules", "seconds": 5 }"#),
+            ],
+            None,
+        );
+
+        assert_eq!(
+            shown(
+                &mut board,
+                at(MONDAY, 12, 0),
+                &[0, 9, 10, 12, 13, 17, 18, 27, 28]
+            ),
+            [
+                "greeting", "greeting", "Tutoring", "Tutoring", "Rules", "Rules", "greeting",
+                "greeting", "Tutoring",
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_messages_that_are_not_active() {
+        let mut board = MessageBoard::new(
+            vec![
+                message(r#"{ "text": "Morning", "end": "12:00" }"#),
+                message(r#"{ "text": "Afternoon", "start": "12:00" }"#),
+            ],
+            None,
+        );
+
+        assert_eq!(
+            shown(&mut board, at(MONDAY, 13, 0), &[0, 10, 20]),
+            ["greeting", "Afternoon", "greeting"]
+        );
+    }
+
+    #[test]
+    fn restarts_when_the_current_slot_goes_away() {
+        let mut board = MessageBoard::new(
+            vec![
+                message(r#"{ "text": "Morning", "end": "12:00" }"#),
+                message(r#"{ "text": "Lunch", "end": "12:00" }"#),
+            ],
+            None,
+        );
+
+        assert_eq!(
+            shown(&mut board, at(MONDAY, 11, 59), &[0, 10, 20]),
+            ["greeting", "Morning", "Lunch"]
+        );
+        assert_eq!(shown(&mut board, at(MONDAY, 12, 0), &[0]), ["greeting"]);
+    }
+}
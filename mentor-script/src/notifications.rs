@@ -0,0 +1,214 @@
+//! Desktop notifications
+//!
+//! Shows Pending and Active reminders as freedesktop notifications over D-Bus
+//! (`org.freedesktop.Notifications`) and reports their "Checked" and "Snooze"
+//! buttons back to the app. On other platforms notifications are skipped.
+//!
+//! The D-Bus calls sit behind [`NotificationBackend`], so tests can stand in
+//! for the session bus.
+
+use eframe::egui::Context;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Button pressed on a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    /// The mentor did the check
+    Checked,
+    /// Bring the reminder back in a few minutes
+    Snooze,
+}
+
+impl NotificationAction {
+    /// Action key sent over D-Bus and the button label shown for it
+    const BUTTONS: [(&'static str, &'static str, NotificationAction); 2] = [
+        ("checked", "Checked", NotificationAction::Checked),
+        ("snooze", "Snooze", NotificationAction::Snooze),
+    ];
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::BUTTONS
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, _, action)| *action)
+    }
+}
+
+/// A notification to put on screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    /// Stays up until acted on or closed
+    pub urgent: bool,
+}
+
+/// Reports button presses on one notification back to its [`Notifier`]
+#[derive(Debug, Clone)]
+pub struct ActionSender {
+    generation: u64,
+    sender: Sender<(u64, NotificationAction)>,
+    /// Woken up so the press is handled even while the window is hidden
+    ctx: Context,
+}
+
+impl ActionSender {
+    /// Reports a button press; ignored once the notification has been replaced
+    pub fn send(&self, action: NotificationAction) {
+        let _ = self.sender.send((self.generation, action));
+        self.ctx.request_repaint();
+    }
+}
+
+/// Puts notifications on screen, e.g. over the D-Bus session bus
+pub trait NotificationBackend {
+    /// Shows `notification` and keeps it up until `close` is signalled or
+    /// dropped, reporting its button presses to `actions`. Must not block.
+    fn show(&self, notification: Notification, actions: ActionSender, close: Receiver<()>);
+}
+
+/// Shows one notification at a time and collects button presses
+pub struct Notifier {
+    /// Where notifications go; none when they're turned off
+    backend: Option<Box<dyn NotificationBackend>>,
+    ctx: Context,
+    /// Number of the notification currently shown, so stale actions are ignored
+    generation: u64,
+    /// Dropping this closes the current notification
+    close: Option<Sender<()>>,
+    actions_tx: Sender<(u64, NotificationAction)>,
+    actions_rx: Receiver<(u64, NotificationAction)>,
+}
+
+impl Notifier {
+    /// Creates a notifier; when `enabled` is false nothing is ever shown.
+    ///
+    /// `ctx` is woken up whenever a button is pressed.
+    pub fn new(enabled: bool, ctx: &Context) -> Self {
+        let backend = enabled.then(|| Box::new(DBusBackend) as Box<dyn NotificationBackend>);
+        Self::with_backend(backend, ctx)
+    }
+
+    /// Creates a notifier showing notifications through `backend`
+    pub fn with_backend(backend: Option<Box<dyn NotificationBackend>>, ctx: &Context) -> Self {
+        let (actions_tx, actions_rx) = mpsc::channel();
+        Self {
+            backend,
+            ctx: ctx.clone(),
+            generation: 0,
+            close: None,
+            actions_tx,
+            actions_rx,
+        }
+    }
+
+    /// Replaces the current notification with a new one.
+    ///
+    /// `urgent` notifications stay up until acted on or closed.
+    pub fn show(&mut self, summary: &str, body: &str, urgent: bool) {
+        self.close();
+        let Some(backend) = &self.backend else {
+            return;
+        };
+
+        self.generation += 1;
+        let (close_tx, close_rx) = mpsc::channel();
+        self.close = Some(close_tx);
+        backend.show(
+            Notification {
+                summary: summary.to_string(),
+                body: body.to_string(),
+                urgent,
+            },
+            ActionSender {
+                generation: self.generation,
+                sender: self.actions_tx.clone(),
+                ctx: self.ctx.clone(),
+            },
+            close_rx,
+        );
+    }
+
+    /// Closes the current notification, if any
+    pub fn close(&mut self) {
+        self.close = None;
+    }
+
+    /// Returns the next button pressed on the current notification
+    pub fn poll(&mut self) -> Option<NotificationAction> {
+        while let Ok((generation, action)) = self.actions_rx.try_recv() {
+            if generation == self.generation {
+                return Some(action);
+            }
+        }
+        None
+    }
+}
+
+/// Sends notifications to the desktop's notification server on the session bus
+struct DBusBackend;
+
+/// Shows notifications from a background thread, since D-Bus calls block.
+///
+/// The thread keeps the notification up until `close` is signalled or dropped,
+/// while a second thread waits for a button press and sends it to `actions`.
+#[cfg(all(unix, not(target_os = "macos")))]
+impl NotificationBackend for DBusBackend {
+    fn show(&self, notification: Notification, actions: ActionSender, close: Receiver<()>) {
+        use notify_rust::{ActionResponse, Timeout, Urgency};
+        use std::thread;
+
+        thread::spawn(move || {
+            let urgent = notification.urgent;
+            let mut dbus_notification = notify_rust::Notification::new();
+            dbus_notification
+                .appname("Mentor Script")
+                .summary(&notification.summary)
+                .body(&notification.body)
+                .urgency(if urgent {
+                    Urgency::Critical
+                } else {
+                    Urgency::Normal
+                })
+                .timeout(if urgent {
+                    Timeout::Never
+                } else {
+                    Timeout::Default
+                });
+            for (key, label, _) in NotificationAction::BUTTONS {
+                dbus_notification.action(key, label);
+            }
+
+            let handle = match dbus_notification.show() {
+                Ok(handle) => handle,
+                Err(e) => {
+                    tracing::warn!("Failed to show desktop notification: {e}");
+                    return;
+                }
+            };
+
+            let id = handle.id();
+            thread::spawn(move || {
+                let result = notify_rust::handle_action(id, |response| {
+                    if let ActionResponse::Custom(key) = response
+                        && let Some(action) = NotificationAction::from_key(key)
+                    {
+                        actions.send(action);
+                    }
+                });
+                if let Err(e) = result {
+                    tracing::warn!("Failed to listen for notification actions: {e}");
+                }
+            });
+
+            // Blocks until the app replaces or closes this notification.
+            let _ = close.recv();
+            handle.close();
+        });
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+impl NotificationBackend for DBusBackend {
+    fn show(&self, _notification: Notification, _actions: ActionSender, _close: Receiver<()>) {}
+}
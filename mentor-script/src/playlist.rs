@@ -0,0 +1,114 @@
+//! Playlist file parsing
+//!
+//! Reads M3U/M3U8 and PLS playlists so they can be used as song sources.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Returns true if the path has a playlist extension (.m3u, .m3u8, .pls)
+pub fn is_playlist(path: &Path) -> bool {
+    matches!(
+        lowercase_extension(path).as_deref(),
+        Some("m3u" | "m3u8" | "pls")
+    )
+}
+
+/// Returns the file extension of a path in lowercase, if any
+pub fn lowercase_extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+}
+
+/// Reads a playlist file and returns the entries it lists.
+///
+/// Relative entries are resolved against the playlist's own folder. Remote
+/// (URL) entries are skipped since only local files can be played.
+pub fn read_playlist(path: &Path) -> Vec<PathBuf> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(_) => return Vec::new(), // playlist unreadable -> no entries
+    };
+
+    let base = path.parent().unwrap_or(Path::new(""));
+
+    let entries = if lowercase_extension(path).as_deref() == Some("pls") {
+        parse_pls(&raw)
+    } else {
+        parse_m3u(&raw)
+    };
+
+    entries
+        .into_iter()
+        .filter(|entry| !entry.contains("://"))
+        .map(|entry| base.join(entry))
+        .collect()
+}
+
+/// Extracts file entries from M3U content, ignoring comments and `#EXT` directives
+fn parse_m3u(raw: &str) -> Vec<String> {
+    raw.lines()
+        .map(|line| line.trim().trim_start_matches('\u{feff}'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Extracts `FileN=` entries from PLS content
+fn parse_pls(raw: &str) -> Vec<String> {
+    raw.lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .filter(|(key, _)| {
+            key.get(..4).is_some_and(|k| k.eq_ignore_ascii_case("file"))
+                && key.len() > 4
+                && key[4..].chars().all(|c| c.is_ascii_digit())
+        })
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn m3u_skips_comments_and_blank_lines() {
+        let raw =
+            "\u{feff}#EXTM3U\n#EXTINF:123,Artist - Title\nsong.mp3\n\n  sub/other.ogg  \n# note\n";
+        assert_eq!(parse_m3u(raw), ["song.mp3", "sub/other.ogg"]);
+    }
+
+    #[test]
+    fn pls_keeps_only_numbered_file_entries() {
+        let raw = "[playlist]\nFile1=one.mp3\nTitle1=One\nfile2=two.flac\nFile=bare.mp3\nFileX=x.mp3\nFile3=\nNumberOfEntries=3\n";
+        assert_eq!(parse_pls(raw), ["one.mp3", "two.flac"]);
+    }
+
+    #[test]
+    fn recognizes_playlist_extensions() {
+        assert!(is_playlist(Path::new("mix.M3U")));
+        assert!(is_playlist(Path::new("mix.m3u8")));
+        assert!(is_playlist(Path::new("radio.pls")));
+        assert!(!is_playlist(Path::new("song.mp3")));
+        assert!(!is_playlist(Path::new("m3u")));
+    }
+
+    #[test]
+    fn read_playlist_resolves_relative_entries_and_skips_urls() {
+        let dir =
+            std::env::temp_dir().join(format!("mentor-script-playlist-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mix.pls");
+        fs::write(
+            &path,
+            "[playlist]\nFile1=song.mp3\nFile2=http://radio.example/stream\n",
+        )
+        .unwrap();
+
+        assert_eq!(read_playlist(&path), [dir.join("song.mp3")]);
+        assert!(read_playlist(&dir.join("missing.m3u")).is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
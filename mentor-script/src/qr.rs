@@ -0,0 +1,72 @@
+//! QR codes drawn with the egui painter
+//!
+//! Lets a link be scanned from a phone without any network access or images.
+
+use eframe::egui::{self, Color32, Id, Rect, Response, Sense, Ui, pos2, vec2};
+use eframe::emath::GuiRounding;
+use qrcodegen::{QrCode, QrCodeEcc};
+use std::sync::Arc;
+
+/// Modules of blank border around the code, as the QR spec asks for
+const QUIET_ZONE: i32 = 4;
+
+/// Draws `text` as a QR code `size` points wide, black on white.
+///
+/// Shows the reason instead if the text is too long to encode.
+pub fn qr_code(ui: &mut Ui, text: &str, size: f32) -> Response {
+    let Some(code) = encode(ui, text) else {
+        return ui.label("Link too long for a QR code");
+    };
+
+    let (rect, response) = ui.allocate_exact_size(vec2(size, size), Sense::hover());
+    if !ui.is_rect_visible(rect) {
+        return response;
+    }
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, Color32::WHITE);
+
+    let modules = code.size() + QUIET_ZONE * 2;
+    let module = size / modules as f32;
+    // Module edges snapped to whole pixels, so neighbours share an edge with
+    // no hairline gap and every module keeps its size.
+    let pixels_per_point = painter.pixels_per_point();
+    let edge = |origin: f32, i: i32| {
+        (origin + (i + QUIET_ZONE) as f32 * module).round_to_pixels(pixels_per_point)
+    };
+    for y in 0..code.size() {
+        for x in 0..code.size() {
+            if code.get_module(x, y) {
+                let cell = Rect::from_min_max(
+                    pos2(edge(rect.min.x, x), edge(rect.min.y, y)),
+                    pos2(edge(rect.min.x, x + 1), edge(rect.min.y, y + 1)),
+                );
+                painter.rect_filled(cell, 0.0, Color32::BLACK);
+            }
+        }
+    }
+
+    response
+}
+
+/// Encodes `text`, reusing the last encoding of the same text between frames
+fn encode(ui: &Ui, text: &str) -> Option<Arc<QrCode>> {
+    let id = Id::new(("qr_code", text));
+    if let Some(code) = ui.data(|d| d.get_temp::<Arc<QrCode>>(id)) {
+        return Some(code);
+    }
+
+    let code = Arc::new(QrCode::encode_text(text, QrCodeEcc::Medium).ok()?);
+    ui.data_mut(|d| d.insert_temp(id, code.clone()));
+    Some(code)
+}
+
+/// Read-only copy of a link with a button to put it on the clipboard
+pub fn copyable_link(ui: &mut Ui, url: &str) {
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut &*url).desired_width(320.0));
+        if ui.button("Copy").clicked() {
+            ui.ctx().copy_text(url.to_string());
+        }
+    });
+}
@@ -0,0 +1,104 @@
+//! Mentor shift roster
+//!
+//! Maps weekdays and time ranges to the mentor on duty, along with their
+//! personal greeting and song source.
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// A single shift in the roster
+#[derive(Debug, Deserialize, Clone)]
+pub struct RosterEntry {
+    /// Name of the mentor working this shift
+    pub mentor: String,
+    /// Weekdays this shift repeats on (e.g. "Mon", "Tuesday"). Empty means every day.
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Time the shift starts (e.g. "10:00")
+    pub start: NaiveTime,
+    /// Time the shift ends, exclusive (e.g. "12:00")
+    pub end: NaiveTime,
+
+    /// Greeting shown on the idle screen instead of `mentor_text`
+    #[serde(default)]
+    pub greeting: Option<String>,
+
+    /// Song folder or playlist for this mentor.
+    ///
+    /// If relative, it is resolved relative to the executable's directory.
+    /// When unset, the shared `songs_dir` is used.
+    #[serde(default)]
+    pub songs_dir: Option<PathBuf>,
+
+    /// Audio files discovered from this entry's `songs_dir`
+    #[serde(skip)]
+    pub songs: Vec<PathBuf>,
+}
+
+impl RosterEntry {
+    /// Returns true if this shift covers the given moment
+    pub fn covers(&self, now: DateTime<Local>) -> bool {
+        let on_day = self.days.is_empty() || self.days.contains(&now.weekday());
+        let time = now.time();
+
+        on_day && self.start <= time && time < self.end
+    }
+}
+
+/// Returns the first roster entry covering the given moment, if any
+pub fn on_duty(roster: &[RosterEntry], now: DateTime<Local>) -> Option<&RosterEntry> {
+    roster.iter().find(|entry| entry.covers(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::local as at;
+
+    const MONDAY: (i32, u32, u32) = (2026, 6, 1);
+    const TUESDAY: (i32, u32, u32) = (2026, 6, 2);
+
+    fn shift(mentor: &str, days: &str, start: &str, end: &str) -> RosterEntry {
+        serde_json::from_str(&format!(
+            r#"{{ "mentor": "{mentor}", "days": {days}, "start": "{start}", "end": "{end}" }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn shift_covers_start_but_not_end() {
+        let morning = shift("Ada", r#"["Mon"]"#, "10:00", "12:00");
+
+        assert!(!morning.covers(at(MONDAY, 9, 59)));
+        assert!(morning.covers(at(MONDAY, 10, 0)));
+        assert!(morning.covers(at(MONDAY, 11, 59)));
+        assert!(!morning.covers(at(MONDAY, 12, 0)));
+        assert!(!morning.covers(at(TUESDAY, 10, 0)), "wrong weekday");
+    }
+
+    #[test]
+    fn shift_without_days_covers_every_day() {
+        let daily = shift("Grace", "[]", "10:00", "12:00");
+        assert!(daily.covers(at(MONDAY, 11, 0)));
+        assert!(daily.covers(at(TUESDAY, 11, 0)));
+    }
+
+    #[test]
+    fn first_matching_shift_is_on_duty() {
+        let roster = [
+            shift("Ada", r#"["Monday"]"#, "10:00", "12:00"),
+            shift("Grace", "[]", "10:00", "14:00"),
+        ];
+
+        let mentor = |date, hour| on_duty(&roster, at(date, hour, 0)).map(|e| e.mentor.as_str());
+        assert_eq!(mentor(MONDAY, 11), Some("Ada"));
+        assert_eq!(
+            mentor(MONDAY, 12),
+            Some("Grace"),
+            "handover at the end time"
+        );
+        assert_eq!(mentor(MONDAY, 14), None);
+        assert_eq!(mentor(TUESDAY, 11), Some("Grace"));
+    }
+}
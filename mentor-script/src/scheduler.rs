@@ -1,55 +1,384 @@
 //! Scheduling logic for reminder timing
 //!
-//! Determines when reminders should trigger and calculates time until next check.
+//! Determines when reminders should trigger, lists upcoming checks within lab
+//! hours, and calculates time until the next check.
 
+use chrono::{DateTime, Duration, DurationRound, Local, NaiveDate, NaiveTime, Timelike};
+use serde::Deserialize;
 use std::fmt;
 use std::fmt::Formatter;
-use chrono::{DateTime, Local, Timelike};
+use std::iter;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::Arc;
 
-/// Type of check-in reminder
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CheckType {
-    /// 30-minute check (triggers at :30)
-    HalfHour,
-    /// Hourly check (triggers at :55)
-    Hour,
+/// When a check goes off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Trigger {
+    /// Every hour at this minute, written ":30"
+    Hourly(u32),
+    /// Every day at this time, written "17:45"
+    Daily(NaiveTime),
 }
 
+impl TryFrom<String> for Trigger {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.trim();
+
+        if let Some(minute) = value.strip_prefix(':').or_else(|| value.strip_prefix("*:")) {
+            return match minute.parse::<u32>() {
+                Ok(minute) if minute < 60 => Ok(Trigger::Hourly(minute)),
+                _ => Err(format!("invalid minute in trigger \"{value}\"")),
+            };
+        }
+
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .map(Trigger::Daily)
+            .map_err(|_| format!("invalid trigger \"{value}\", expected \":MM\" or \"HH:MM\""))
+    }
+}
+
+impl Trigger {
+    /// Returns the minute this trigger fires within the given hour, if it does
+    fn minute_in_hour(&self, hour: u32) -> Option<u32> {
+        match self {
+            Trigger::Hourly(minute) => Some(*minute),
+            Trigger::Daily(time) => (time.hour() == hour).then_some(time.minute()),
+        }
+    }
+}
+
+/// A kind of check defined in config, e.g. the half hour headcount
+#[derive(Debug, Deserialize, Clone)]
+pub struct CheckDefinition {
+    /// Short unique name (e.g. "half_hour")
+    pub id: String,
+    /// Text shown on screen (e.g. "Half hour check")
+    pub label: String,
+    /// Form opened by the "Open Form" button, if this check has one
+    #[serde(default)]
+    pub form_url: Option<String>,
+    /// When this check goes off
+    pub triggers: Vec<Trigger>,
+    /// Accent color as a hex string (e.g. "#23F123")
+    #[serde(default)]
+    pub color: Option<String>,
+
+    /// Song folder or playlist played for this check instead of the usual songs.
+    ///
+    /// If relative, it is resolved relative to the executable's directory.
+    #[serde(default)]
+    pub sounds: Option<PathBuf>,
+
+    /// Audio files discovered from `sounds`
+    #[serde(skip)]
+    pub songs: Vec<PathBuf>,
+}
+
+/// Type of check-in reminder, cheap to clone and compared by id
+#[derive(Debug, Clone)]
+pub struct CheckType(Arc<CheckDefinition>);
+
+impl CheckType {
+    pub fn new(definition: CheckDefinition) -> Self {
+        Self(Arc::new(definition))
+    }
+}
+
+impl Deref for CheckType {
+    type Target = CheckDefinition;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PartialEq for CheckType {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for CheckType {}
+
 impl fmt::Display for CheckType {
-   /// Implements display output for each CheckType for better formatting.
+    /// Implements display output for each CheckType using its configured label.
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            CheckType::Hour => write!(f, "One hour check"),
-            CheckType::HalfHour => write!(f, "Half hour check"),
+        write!(f, "{}", self.label)
+    }
+}
+
+/// How far ahead `Schedule::upcoming` searches before giving up
+const MAX_SEARCH_DAYS: i64 = 366;
+
+/// Daily hours the lab is open; checks only happen in this window
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct LabHours {
+    /// Opening time (e.g. "10:00")
+    pub open: NaiveTime,
+    /// Closing time, exclusive (e.g. "18:00")
+    pub close: NaiveTime,
+}
+
+impl Default for LabHours {
+    fn default() -> Self {
+        Self {
+            open: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            close: NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
         }
     }
 }
 
-/// Returns the type of check if the current time matches a reminder trigger
-///
-/// Triggers at minute :30 (HalfHour) and :55 (Hour)
-pub fn check_time() -> Option<CheckType> {
-    let now = Local::now();
-    let minute = now.minute();
+/// Which checks exist and when the lab is open, and therefore when checks happen
+#[derive(Debug, Clone, Default)]
+pub struct Schedule {
+    hours: LabHours,
+    closures: Vec<NaiveDate>,
+    checks: Vec<CheckType>,
+}
+
+impl Schedule {
+    /// Creates a schedule from daily lab hours, closure dates and the check types
+    pub fn new(hours: LabHours, closures: Vec<NaiveDate>, checks: Vec<CheckType>) -> Self {
+        Self {
+            hours,
+            closures,
+            checks,
+        }
+    }
+
+    /// Returns all configured check types
+    pub fn checks(&self) -> &[CheckType] {
+        &self.checks
+    }
+
+    /// Returns the check whose trigger matches the given minute, if any.
+    ///
+    /// When several checks share a minute, the first configured one wins.
+    pub fn check_time(&self, now: DateTime<Local>) -> Option<CheckType> {
+        self.checks
+            .iter()
+            .find(|check| {
+                check
+                    .triggers
+                    .iter()
+                    .any(|t| t.minute_in_hour(now.hour()) == Some(now.minute()))
+            })
+            .cloned()
+    }
+
+    /// Returns true if the lab is open (and checks are due) at the given time
+    pub fn is_open(&self, time: DateTime<Local>) -> bool {
+        let t = time.time();
+        self.hours.open <= t && t < self.hours.close && !self.closures.contains(&time.date_naive())
+    }
+
+    /// Returns today's closing time if the lab is open at `now`
+    pub fn closing_time(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        if !self.is_open(now) {
+            return None;
+        }
+
+        now.date_naive()
+            .and_time(self.hours.close)
+            .and_local_timezone(Local)
+            .earliest()
+    }
+
+    /// Yields every check after `after` while the lab is open, soonest first.
+    ///
+    /// Each trigger is the start of its minute (e.g. 14:30:00). Closed hours
+    /// and closure dates are skipped; the search stops after a year.
+    pub fn upcoming(
+        &self,
+        after: DateTime<Local>,
+    ) -> impl Iterator<Item = (DateTime<Local>, CheckType)> + '_ {
+        let start = after.duration_trunc(Duration::hours(1)).unwrap_or(after);
+        let horizon = after + Duration::days(MAX_SEARCH_DAYS);
+        // Without a trigger inside lab hours there's nothing to find, so skip the search.
+        let searching = self.triggers_while_open();
+
+        iter::successors(Some(start), |hour| Some(*hour + Duration::hours(1)))
+            .take_while(move |hour| searching && *hour <= horizon)
+            .flat_map(move |hour| self.triggers_in_hour(hour))
+            .filter(move |(at, _)| *at > after && self.is_open(*at))
+    }
 
-    match minute {
-        30 => Some(CheckType::HalfHour),
-        55 => Some(CheckType::Hour),
-        _ => None,
+    /// Returns true if any check has a trigger that falls inside lab hours
+    fn triggers_while_open(&self) -> bool {
+        let in_hours = |time: NaiveTime| self.hours.open <= time && time < self.hours.close;
+        self.checks
+            .iter()
+            .flat_map(|check| check.triggers.iter())
+            .any(|trigger| match trigger {
+                Trigger::Hourly(minute) => (0..24)
+                    .any(|hour| NaiveTime::from_hms_opt(hour, *minute, 0).is_some_and(in_hours)),
+                Trigger::Daily(time) => in_hours(*time),
+            })
     }
 
+    /// Lists every check triggering within the hour starting at `hour`, in time order.
+    ///
+    /// Only the first configured check is kept when several share a minute.
+    fn triggers_in_hour(&self, hour: DateTime<Local>) -> Vec<(DateTime<Local>, CheckType)> {
+        let mut triggers: Vec<_> = self
+            .checks
+            .iter()
+            .flat_map(|check| {
+                check
+                    .triggers
+                    .iter()
+                    .filter_map(move |t| t.minute_in_hour(hour.hour()))
+                    .map(move |minute| (hour + Duration::minutes(minute.into()), check.clone()))
+            })
+            .collect();
+
+        triggers.sort_by_key(|(at, _)| *at);
+        triggers.dedup_by_key(|(at, _)| *at);
+        triggers
+    }
+
+    /// Returns the next check after `now` and when it triggers
+    pub fn next_check(&self, now: DateTime<Local>) -> Option<(DateTime<Local>, CheckType)> {
+        self.upcoming(now).next()
+    }
+
+    /// Lists the checks in `(after, until]`, oldest first.
+    ///
+    /// At most the last day is searched.
+    pub fn triggers_between(
+        &self,
+        after: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> Vec<(DateTime<Local>, CheckType)> {
+        let after = after.max(until - Duration::days(1));
+        self.upcoming(after)
+            .take_while(|(at, _)| *at <= until)
+            .collect()
+    }
 }
 
-/// Calculates which check is next and how many minutes until it triggers
-///
-/// Returns (CheckType, minutes_remaining)
-pub fn minutes_until_next_check(now: DateTime<Local>) -> (CheckType, i64) {
-    let m = now.minute();
-    if m < 30 {
-        (CheckType::HalfHour, (30 - m) as i64)
-    } else if m < 55 {
-        (CheckType::Hour, (55 - m) as i64)
-    } else {
-        (CheckType::HalfHour, (60 - m + 30) as i64)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::local as at;
+
+    fn check(id: &str, triggers: &[&str]) -> CheckType {
+        crate::test_util::check(id, id, triggers)
+    }
+
+    /// The standard :30 and :55 checks, open 10:00–18:00
+    fn standard(closures: Vec<NaiveDate>) -> Schedule {
+        Schedule::new(
+            LabHours::default(),
+            closures,
+            vec![check("half_hour", &[":30"]), check("hour", &[":55"])],
+        )
+    }
+
+    const MONDAY: (i32, u32, u32) = (2026, 6, 1);
+    const TUESDAY: (i32, u32, u32) = (2026, 6, 2);
+    const WEDNESDAY: (i32, u32, u32) = (2026, 6, 3);
+
+    #[test]
+    fn parses_hourly_and_daily_triggers() {
+        assert_eq!(
+            Trigger::try_from(":30".to_string()),
+            Ok(Trigger::Hourly(30))
+        );
+        assert_eq!(
+            Trigger::try_from(" *:05 ".to_string()),
+            Ok(Trigger::Hourly(5))
+        );
+        assert_eq!(
+            Trigger::try_from("17:45".to_string()),
+            Ok(Trigger::Daily(NaiveTime::from_hms_opt(17, 45, 0).unwrap()))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_triggers() {
+        for bad in [":60", ":", ":ab", ":-1", "25:00", "17:60", "noon", ""] {
+            assert!(
+                Trigger::try_from(bad.to_string()).is_err(),
+                "accepted {bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn next_check_within_the_hour() {
+        let schedule = standard(Vec::new());
+        let (next_at, next) = schedule.next_check(at(MONDAY, 14, 31)).unwrap();
+        assert_eq!(next_at, at(MONDAY, 14, 55));
+        assert_eq!(next.id, "hour");
+    }
+
+    #[test]
+    fn rolls_to_next_open_day_after_closing() {
+        let schedule = standard(Vec::new());
+        let (next_at, next) = schedule.next_check(at(MONDAY, 17, 56)).unwrap();
+        assert_eq!(next_at, at(TUESDAY, 10, 30));
+        assert_eq!(next.id, "half_hour");
+    }
+
+    #[test]
+    fn skips_closures() {
+        let tuesday = NaiveDate::from_ymd_opt(TUESDAY.0, TUESDAY.1, TUESDAY.2).unwrap();
+        let schedule = standard(vec![tuesday]);
+
+        assert!(!schedule.is_open(at(TUESDAY, 12, 0)));
+        let (next_at, _) = schedule.next_check(at(MONDAY, 17, 56)).unwrap();
+        assert_eq!(next_at, at(WEDNESDAY, 10, 30));
+    }
+
+    #[test]
+    fn keeps_first_check_when_minutes_match() {
+        let schedule = Schedule::new(
+            LabHours::default(),
+            Vec::new(),
+            vec![check("first", &[":30"]), check("second", &[":30", "14:45"])],
+        );
+
+        let checks: Vec<_> = schedule
+            .triggers_between(at(MONDAY, 14, 0), at(MONDAY, 15, 0))
+            .into_iter()
+            .map(|(at, check)| (at, check.id.clone()))
+            .collect();
+        assert_eq!(
+            checks,
+            vec![
+                (at(MONDAY, 14, 30), "first".to_string()),
+                (at(MONDAY, 14, 45), "second".to_string()),
+            ]
+        );
+        assert_eq!(schedule.check_time(at(MONDAY, 14, 30)).unwrap().id, "first");
+    }
+
+    #[test]
+    fn no_checks_outside_lab_hours() {
+        let schedule = standard(Vec::new());
+        assert!(
+            schedule
+                .triggers_between(at(MONDAY, 18, 0), at(TUESDAY, 10, 0))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn nothing_upcoming_without_triggers_in_lab_hours() {
+        assert_eq!(Schedule::default().next_check(at(MONDAY, 12, 0)), None);
+
+        let after_hours = Schedule::new(
+            LabHours::default(),
+            Vec::new(),
+            vec![check("evening", &["19:00"])],
+        );
+        assert_eq!(after_hours.next_check(at(MONDAY, 12, 0)), None);
+        assert!(!after_hours.triggers_while_open());
+        assert!(standard(Vec::new()).triggers_while_open());
     }
 }
@@ -2,30 +2,337 @@
 //!
 //! Handles playing sound files when reminders trigger.
 
-use rodio::{Decoder, OutputStream, OutputStreamBuilder, Sink};
+use rodio::cpal::traits::HostTrait;
+use rodio::source::SineWave;
+use rodio::{Decoder, DeviceTrait, OutputStream, OutputStreamBuilder, Sink, Source};
 use std::fs::File;
-use std::io::{BufReader};
-use std::path::PathBuf;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey};
+use symphonia::core::probe::Hint;
+
+/// How long to wait between attempts to rebuild a lost audio stream
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Why a file couldn't be played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayError {
+    /// The file couldn't be opened or decoded; other files may still play
+    File,
+    /// The output stream has failed and needs rebuilding
+    Output,
+}
 
 /// Audio output stream handler
 pub struct Audio {
     stream: OutputStream,
+    /// Set by the stream's error callback once the device reports a failure
+    broken: Arc<AtomicBool>,
 }
 
 impl Audio {
-    /// Creates a new audio output stream
-    pub fn new() -> Option<Self> {
-        let stream = OutputStreamBuilder::open_default_stream().ok()?;
-        Some(Self { stream })
+    /// Creates a new audio output stream.
+    ///
+    /// When `device_name` is set, the output device with that name is used.
+    /// If it can't be found or opened, the default device is used instead.
+    pub fn new(device_name: Option<&str>) -> Option<Self> {
+        let broken = Arc::new(AtomicBool::new(false));
+        let on_error = {
+            let broken = Arc::clone(&broken);
+            move |e: rodio::cpal::StreamError| {
+                tracing::error!("Audio stream error: {e}");
+                broken.store(true, Ordering::Relaxed);
+            }
+        };
+
+        if let Some(name) = device_name {
+            match Self::find_device(name) {
+                Some(device) => {
+                    match OutputStreamBuilder::from_device(device).and_then(|builder| {
+                        builder
+                            .with_error_callback(on_error.clone())
+                            .open_stream_or_fallback()
+                    }) {
+                        Ok(stream) => return Some(Self { stream, broken }),
+                        Err(e) => tracing::warn!(
+                            "Failed to open audio device \"{name}\": {e}; using default device"
+                        ),
+                    }
+                }
+                None => tracing::warn!("Audio device \"{name}\" not found; using default device"),
+            }
+        }
+
+        let stream = OutputStreamBuilder::from_default_device()
+            .and_then(|builder| {
+                builder
+                    .with_error_callback(on_error)
+                    .open_stream_or_fallback()
+            })
+            .ok()?;
+        Some(Self { stream, broken })
+    }
+
+    /// Returns true once the output device has reported a stream failure
+    pub fn is_broken(&self) -> bool {
+        self.broken.load(Ordering::Relaxed)
+    }
+
+    /// Returns the names of all available output devices
+    pub fn output_device_names() -> Vec<String> {
+        rodio::cpal::default_host()
+            .output_devices()
+            .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Finds an output device by name.
+    ///
+    /// An exact (case-insensitive) match wins, otherwise the first device
+    /// whose name contains `name` is used.
+    fn find_device(name: &str) -> Option<rodio::Device> {
+        let wanted = name.to_lowercase();
+        let mut devices: Vec<(String, rodio::Device)> = rodio::cpal::default_host()
+            .output_devices()
+            .ok()?
+            .filter_map(|d| Some((d.name().ok()?.to_lowercase(), d)))
+            .collect();
+
+        let index = devices
+            .iter()
+            .position(|(n, _)| *n == wanted)
+            .or_else(|| devices.iter().position(|(n, _)| n.contains(&wanted)))?;
+
+        Some(devices.swap_remove(index).1)
     }
 
     /// Plays an audio file and returns a sink for controlling playback
-    pub fn play_file(&self, path: PathBuf) -> Option<Sink> {
-        let file = File::open(path).ok()?;
-        let source = Decoder::new(BufReader::new(file)).ok()?;
+    pub fn play_file(&self, path: PathBuf) -> Result<Sink, PlayError> {
+        let file = File::open(&path).map_err(|e| {
+            tracing::error!("Failed to open {}: {e}", path.display());
+            PlayError::File
+        })?;
+        let source = Decoder::new(BufReader::new(file)).map_err(|e| {
+            tracing::error!("Failed to decode {}: {e}", path.display());
+            PlayError::File
+        })?;
+
+        if self.is_broken() {
+            tracing::error!("Failed to play {}: audio stream has failed", path.display());
+            return Err(PlayError::Output);
+        }
 
         let sink = Sink::connect_new(self.stream.mixer());
         sink.append(source);
-        Some(sink)
+        Ok(sink)
+    }
+
+    /// Plays a short chime, from a file when given or a soft two-tone otherwise.
+    ///
+    /// The chime plays to completion on its own alongside any music.
+    pub fn play_chime(&self, path: Option<&Path>) {
+        if let Some(path) = path {
+            match self.play_file(path.to_path_buf()) {
+                Ok(sink) => sink.detach(),
+                Err(_) => tracing::error!("Failed to play chime {}", path.display()),
+            }
+            return;
+        }
+
+        let sink = Sink::connect_new(self.stream.mixer());
+        for freq in [880.0, 660.0] {
+            sink.append(
+                SineWave::new(freq)
+                    .take_duration(Duration::from_millis(300))
+                    .fade_in(Duration::from_millis(20))
+                    .amplify(0.15),
+            );
+        }
+        sink.detach();
     }
-}
\ No newline at end of file
+}
+
+/// Health of the audio output, shown in the status indicator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioStatus {
+    /// No stream has been opened yet
+    NotStarted,
+    /// Stream is open and working
+    Ready,
+    /// Stream broke or could not be opened; rebuilding is being retried
+    Unavailable,
+}
+
+/// Owns the audio stream and rebuilds it when the device goes away
+pub struct AudioOutput {
+    device_name: Option<String>,
+    audio: Option<Audio>,
+    status: AudioStatus,
+    last_attempt: Option<Instant>,
+}
+
+impl AudioOutput {
+    /// Creates the output without opening a stream; it is opened on first use
+    pub fn new(device_name: Option<String>) -> Self {
+        Self {
+            device_name,
+            audio: None,
+            status: AudioStatus::NotStarted,
+            last_attempt: None,
+        }
+    }
+
+    /// Returns the current health of the audio output
+    pub fn status(&self) -> AudioStatus {
+        self.status
+    }
+
+    /// Returns a working stream, opening or rebuilding it if needed
+    pub fn get(&mut self) -> Option<&Audio> {
+        if self.audio.as_ref().is_some_and(Audio::is_broken) {
+            self.mark_broken();
+        }
+
+        if self.audio.is_none() {
+            self.open();
+        }
+
+        self.audio.as_ref()
+    }
+
+    /// Drops a stream whose playback failed so the next use rebuilds it
+    pub fn mark_broken(&mut self) {
+        if self.audio.take().is_some() {
+            tracing::warn!("Audio stream lost; will try to rebuild it");
+        }
+        self.status = AudioStatus::Unavailable;
+    }
+
+    /// Detects a broken stream and retries opening a lost one.
+    ///
+    /// Only streams that were opened before are retried, at most once every
+    /// few seconds. Returns true if the stream was recovered by this call.
+    pub fn poll(&mut self) -> bool {
+        if self.audio.as_ref().is_some_and(Audio::is_broken) {
+            self.mark_broken();
+        }
+
+        let retry_due = self
+            .last_attempt
+            .is_none_or(|at| at.elapsed() >= RETRY_INTERVAL);
+
+        self.status == AudioStatus::Unavailable && retry_due && self.open()
+    }
+
+    /// Releases the stream, e.g. on shutdown
+    pub fn close(&mut self) {
+        self.audio = None;
+    }
+
+    /// Tries to open a stream, updating the status. Returns true on recovery.
+    fn open(&mut self) -> bool {
+        self.last_attempt = Some(Instant::now());
+        self.audio = Audio::new(self.device_name.as_deref());
+
+        let recovered = self.audio.is_some() && self.status == AudioStatus::Unavailable;
+        if recovered {
+            tracing::info!("Audio stream recovered");
+        }
+
+        self.status = if self.audio.is_some() {
+            AudioStatus::Ready
+        } else {
+            AudioStatus::Unavailable
+        };
+
+        recovered
+    }
+}
+
+/// Details about a song shown in the now-playing display
+#[derive(Debug, Clone)]
+pub struct TrackInfo {
+    /// File the track was read from
+    pub path: PathBuf,
+    /// Track title from tags, or the file name when untagged
+    pub title: String,
+    /// Track artist from tags, if present
+    pub artist: Option<String>,
+    /// Total length of the track, if the format reports it
+    pub duration: Option<Duration>,
+}
+
+impl TrackInfo {
+    /// Reads the title, artist and duration of an audio file.
+    ///
+    /// Missing or unreadable tags fall back to the file name.
+    pub fn read(path: &Path) -> Self {
+        let mut info = Self {
+            path: path.to_path_buf(),
+            title: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            artist: None,
+            duration: None,
+        };
+
+        let Ok(file) = File::open(path) else {
+            return info;
+        };
+
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
+        }
+
+        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+        let Ok(mut probed) = symphonia::default::get_probe().format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        ) else {
+            return info;
+        };
+
+        // Tags can live in front of the container (e.g. ID3) or inside it.
+        if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+            info.apply_tags(revision);
+        }
+        if let Some(revision) = probed.format.metadata().current() {
+            info.apply_tags(revision);
+        }
+
+        info.duration = probed.format.default_track().and_then(|track| {
+            let params = &track.codec_params;
+            params
+                .time_base
+                .zip(params.n_frames)
+                .map(|(base, frames)| base.calc_time(frames).into())
+        });
+
+        info
+    }
+
+    /// Copies the title and artist tags from a metadata revision, if present
+    fn apply_tags(&mut self, revision: &MetadataRevision) {
+        for tag in revision.tags() {
+            let value = tag.value.to_string();
+            if value.trim().is_empty() {
+                continue;
+            }
+
+            match tag.std_key {
+                Some(StandardTagKey::TrackTitle) => self.title = value,
+                Some(StandardTagKey::Artist) => self.artist = Some(value),
+                _ => {}
+            }
+        }
+    }
+}
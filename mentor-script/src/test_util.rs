@@ -0,0 +1,30 @@
+//! Fixtures shared by the unit tests
+
+use crate::scheduler::{CheckDefinition, CheckType, Trigger};
+use chrono::{DateTime, Local, NaiveDate};
+
+/// A check type with only an id, label and triggers written like ":30" or "17:45"
+pub fn check(id: &str, label: &str, triggers: &[&str]) -> CheckType {
+    CheckType::new(CheckDefinition {
+        id: id.to_string(),
+        label: label.to_string(),
+        form_url: None,
+        triggers: triggers
+            .iter()
+            .map(|t| Trigger::try_from(t.to_string()).unwrap())
+            .collect(),
+        color: None,
+        sounds: None,
+        songs: Vec::new(),
+    })
+}
+
+/// Local time on the given date, e.g. `local((2026, 6, 1), 14, 30)`
+pub fn local(date: (i32, u32, u32), hour: u32, minute: u32) -> DateTime<Local> {
+    NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+        .and_local_timezone(Local)
+        .unwrap()
+}
@@ -0,0 +1,537 @@
+//! Visual themes
+//!
+//! Palette, font sizes and background animation for the UI, picked from a
+//! built-in preset and optionally adjusted in config.
+
+use crate::config::Accessibility;
+use egui::Color32;
+use serde::Deserialize;
+
+/// WCAG AAA contrast ratio for normal text, enforced in high-contrast mode
+const HIGH_CONTRAST_RATIO: f32 = 7.0;
+
+/// WCAG AA contrast ratio for normal text, the minimum for the clock
+const MIN_CLOCK_RATIO: f32 = 4.5;
+
+/// Built-in starting points for a theme
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreset {
+    /// SSE green on a softly shifting background
+    #[default]
+    Sse,
+    /// Muted colors on a plain dark background
+    Dark,
+    /// Bright colors on black for readability from across the room
+    HighContrast,
+}
+
+/// How the window background is drawn
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundMode {
+    /// Slowly cycling RGB around the background color
+    Breathing,
+    /// Plain background color
+    Solid,
+}
+
+/// Theme settings from config.json
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Preset to start from
+    pub preset: ThemePreset,
+    /// Hex colors replacing the preset's
+    pub colors: PaletteOverrides,
+    /// Point sizes replacing the preset's
+    pub font_sizes: FontSizeOverrides,
+    /// Background drawing mode replacing the preset's
+    pub background: Option<BackgroundMode>,
+}
+
+/// Colors used across the UI
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    /// Background, or the center of the breathing cycle
+    pub background: Color32,
+    /// Clock text; `None` uses the inverse of the background
+    pub clock: Option<Color32>,
+    /// Main text: greeting, countdowns, check names
+    pub primary: Color32,
+    /// Upcoming checks, late checks and toasts
+    pub warning: Color32,
+    /// Missed checks and errors
+    pub danger: Color32,
+    /// "Checked" button and completed checks
+    pub success: Color32,
+    /// "Open Form" and songs folder buttons
+    pub info: Color32,
+    /// Music control buttons
+    pub music: Color32,
+    /// After-hours message and closing time
+    pub after_hours: Color32,
+    /// Less important text such as future checks
+    pub muted: Color32,
+    /// Text on filled buttons; `None` keeps egui's default
+    pub button_text: Option<Color32>,
+    /// Text on toasts
+    pub toast_text: Color32,
+}
+
+/// Point sizes used across the UI
+#[derive(Debug, Clone, Copy)]
+pub struct FontSizes {
+    /// "Time to check in!" heading and idle messages
+    pub headline: f32,
+    /// Clock and after-hours message
+    pub large: f32,
+    /// "Upcoming Check" heading
+    pub title: f32,
+    /// Check name, mentor on duty and toasts
+    pub subtitle: f32,
+    /// Countdown text
+    pub body: f32,
+    /// Now playing, late notice and songs button
+    pub detail: f32,
+    /// Buttons, timeline and status line
+    pub small: f32,
+}
+
+/// Everything needed to draw the UI
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub palette: Palette,
+    pub fonts: FontSizes,
+    pub background: BackgroundMode,
+    /// How far each channel swings from the background color while breathing
+    pub breathing_range: f32,
+    /// Animations are frozen
+    pub reduced_motion: bool,
+    /// Contrast ratio every text color is raised to, if any
+    pub min_contrast: Option<f32>,
+}
+
+/// Optional hex colors, one per palette entry
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PaletteOverrides {
+    pub background: Option<String>,
+    pub clock: Option<String>,
+    pub primary: Option<String>,
+    pub warning: Option<String>,
+    pub danger: Option<String>,
+    pub success: Option<String>,
+    pub info: Option<String>,
+    pub music: Option<String>,
+    pub after_hours: Option<String>,
+    pub muted: Option<String>,
+    pub button_text: Option<String>,
+    pub toast_text: Option<String>,
+}
+
+/// Optional point sizes, one per font size entry
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct FontSizeOverrides {
+    pub headline: Option<f32>,
+    pub large: Option<f32>,
+    pub title: Option<f32>,
+    pub subtitle: Option<f32>,
+    pub body: Option<f32>,
+    pub detail: Option<f32>,
+    pub small: Option<f32>,
+}
+
+/// Parses a hex color known to be valid
+fn hex(color: &str) -> Color32 {
+    Color32::from_hex(color).expect("built-in theme colors are valid hex")
+}
+
+impl Theme {
+    /// Builds a theme from a preset with the config's overrides and accessibility settings applied
+    pub fn from_config(config: &ThemeConfig, accessibility: &Accessibility) -> Self {
+        let mut theme = Self::preset(config.preset);
+
+        let colors = &config.colors;
+        let palette = &mut theme.palette;
+        for (value, slot) in [
+            (&colors.background, &mut palette.background),
+            (&colors.primary, &mut palette.primary),
+            (&colors.warning, &mut palette.warning),
+            (&colors.danger, &mut palette.danger),
+            (&colors.success, &mut palette.success),
+            (&colors.info, &mut palette.info),
+            (&colors.music, &mut palette.music),
+            (&colors.after_hours, &mut palette.after_hours),
+            (&colors.muted, &mut palette.muted),
+            (&colors.toast_text, &mut palette.toast_text),
+        ] {
+            if let Some(color) = value.as_deref().and_then(Self::parse_color) {
+                *slot = color;
+            }
+        }
+        for (value, slot) in [
+            (&colors.clock, &mut palette.clock),
+            (&colors.button_text, &mut palette.button_text),
+        ] {
+            if let Some(color) = value.as_deref().and_then(Self::parse_color) {
+                *slot = Some(color);
+            }
+        }
+
+        let sizes = config.font_sizes;
+        let fonts = &mut theme.fonts;
+        for (value, slot) in [
+            (sizes.headline, &mut fonts.headline),
+            (sizes.large, &mut fonts.large),
+            (sizes.title, &mut fonts.title),
+            (sizes.subtitle, &mut fonts.subtitle),
+            (sizes.body, &mut fonts.body),
+            (sizes.detail, &mut fonts.detail),
+            (sizes.small, &mut fonts.small),
+        ] {
+            if let Some(size) = value {
+                *slot = size;
+            }
+        }
+
+        if let Some(background) = config.background {
+            theme.background = background;
+        }
+
+        if accessibility.reduced_motion {
+            theme.reduced_motion = true;
+            theme.background = BackgroundMode::Solid;
+        }
+        if accessibility.high_contrast {
+            theme.apply_high_contrast();
+        }
+
+        theme
+    }
+
+    /// Switches to a solid background and raises every text color to AAA contrast
+    fn apply_high_contrast(&mut self) {
+        self.background = BackgroundMode::Solid;
+        self.min_contrast = Some(HIGH_CONTRAST_RATIO);
+
+        let palette = &mut self.palette;
+        let background = palette.background;
+        for color in [
+            &mut palette.primary,
+            &mut palette.warning,
+            &mut palette.danger,
+            &mut palette.after_hours,
+            &mut palette.muted,
+        ] {
+            *color = with_contrast(*color, background, HIGH_CONTRAST_RATIO);
+        }
+        palette.clock = Some(with_contrast(
+            palette.clock.unwrap_or(Color32::WHITE),
+            background,
+            HIGH_CONTRAST_RATIO,
+        ));
+        palette.toast_text =
+            with_contrast(palette.toast_text, palette.warning, HIGH_CONTRAST_RATIO);
+
+        // Button fills are adjusted to suit their text instead, keeping text color uniform.
+        // This can darken them, so text drawn on the background in these colors goes
+        // through `readable`.
+        let button_text = palette.button_text.unwrap_or(Color32::WHITE);
+        palette.button_text = Some(button_text);
+        for fill in [&mut palette.success, &mut palette.info, &mut palette.music] {
+            *fill = with_contrast(*fill, button_text, HIGH_CONTRAST_RATIO);
+        }
+    }
+
+    /// Returns `color` raised to the theme's minimum contrast against the background.
+    ///
+    /// Used for colors from elsewhere in config, such as check and message colors.
+    pub fn readable(&self, color: Color32) -> Color32 {
+        match self.min_contrast {
+            Some(ratio) => with_contrast(color, self.palette.background, ratio),
+            None => color,
+        }
+    }
+
+    /// Returns one of the built-in themes
+    pub fn preset(preset: ThemePreset) -> Self {
+        let fonts = FontSizes {
+            headline: 48.0,
+            large: 42.0,
+            title: 28.0,
+            subtitle: 24.0,
+            body: 20.0,
+            detail: 18.0,
+            small: 16.0,
+        };
+
+        match preset {
+            ThemePreset::Sse => Self {
+                palette: Palette {
+                    background: hex("#1e1e1e"),
+                    clock: None,
+                    primary: hex("#23F123"),
+                    warning: hex("#f39c12"),
+                    danger: hex("#e74c3c"),
+                    success: hex("#27ae60"),
+                    info: hex("#3498db"),
+                    music: hex("#780000"),
+                    after_hours: hex("#3c7a89"),
+                    muted: Color32::LIGHT_GRAY,
+                    button_text: None,
+                    toast_text: Color32::BLACK,
+                },
+                fonts,
+                background: BackgroundMode::Breathing,
+                breathing_range: 40.0,
+                reduced_motion: false,
+                min_contrast: None,
+            },
+            ThemePreset::Dark => Self {
+                palette: Palette {
+                    background: hex("#121212"),
+                    clock: Some(hex("#bdbdbd")),
+                    primary: hex("#e0e0e0"),
+                    warning: hex("#ffb74d"),
+                    danger: hex("#ef5350"),
+                    success: hex("#2e7d32"),
+                    info: hex("#1565c0"),
+                    music: hex("#6d1b1b"),
+                    after_hours: hex("#5c9ead"),
+                    muted: hex("#9e9e9e"),
+                    button_text: Some(Color32::WHITE),
+                    toast_text: Color32::BLACK,
+                },
+                fonts,
+                background: BackgroundMode::Solid,
+                breathing_range: 20.0,
+                reduced_motion: false,
+                min_contrast: None,
+            },
+            ThemePreset::HighContrast => Self {
+                palette: Palette {
+                    background: Color32::BLACK,
+                    clock: Some(Color32::WHITE),
+                    primary: Color32::WHITE,
+                    warning: hex("#ffd700"),
+                    danger: hex("#ff6b6b"),
+                    success: hex("#00632b"),
+                    info: hex("#0040b0"),
+                    music: hex("#8b0000"),
+                    after_hours: hex("#7fdbff"),
+                    muted: hex("#d0d0d0"),
+                    button_text: Some(Color32::WHITE),
+                    toast_text: Color32::BLACK,
+                },
+                fonts: FontSizes {
+                    small: 18.0,
+                    ..fonts
+                },
+                background: BackgroundMode::Solid,
+                breathing_range: 0.0,
+                reduced_motion: false,
+                min_contrast: None,
+            },
+        }
+    }
+
+    /// Parses a hex color from config, warning about invalid values
+    fn parse_color(value: &str) -> Option<Color32> {
+        let color = Color32::from_hex(value).ok();
+        if color.is_none() {
+            tracing::warn!("Invalid theme color \"{value}\"; keeping the preset's");
+        }
+        color
+    }
+
+    /// Returns a copy with every font size multiplied by `factor`
+    pub fn scaled(&self, factor: f32) -> Self {
+        let fonts = self.fonts;
+        Self {
+            fonts: FontSizes {
+                headline: fonts.headline * factor,
+                large: fonts.large * factor,
+                title: fonts.title * factor,
+                subtitle: fonts.subtitle * factor,
+                body: fonts.body * factor,
+                detail: fonts.detail * factor,
+                small: fonts.small * factor,
+            },
+            ..*self
+        }
+    }
+
+    /// Returns the background color at time `t` (seconds)
+    pub fn background_color(&self, t: f32) -> Color32 {
+        let base = self.palette.background;
+        if self.background == BackgroundMode::Solid {
+            return base;
+        }
+
+        let speed: f32 = 0.2;
+        let phase: f32 = t * speed;
+
+        let r = phase.sin() * 0.5 + 0.5;
+        let g = (phase + 2.0).sin() * 0.5 + 0.5;
+        let b = (phase + 4.0).sin() * 0.5 + 0.5;
+
+        // Swing each channel from the base color up by at most `breathing_range`.
+        let channel =
+            |base: u8, wave: f32| (base as f32 + wave * self.breathing_range).min(255.0) as u8;
+
+        Color32::from_rgb(
+            channel(base.r(), r),
+            channel(base.g(), g),
+            channel(base.b(), b),
+        )
+    }
+
+    /// Returns the clock color for the given background.
+    ///
+    /// Without a configured clock color the inverse of the background is used,
+    /// raised to at least AA contrast since mid-tone backgrounds invert to mid-tones.
+    pub fn clock_color(&self, background: Color32) -> Color32 {
+        let color = self.palette.clock.unwrap_or_else(|| {
+            let [r, g, b, a] = background.to_array();
+            Color32::from_rgba_unmultiplied(255 - r, 255 - g, 255 - b, a)
+        });
+        let ratio = self.min_contrast.unwrap_or(MIN_CLOCK_RATIO);
+        with_contrast(color, background, ratio)
+    }
+
+    /// Returns the Active heading's alpha at time `t` (seconds); constant without motion
+    pub fn pulse_alpha(&self, t: f32) -> u8 {
+        if self.reduced_motion || self.min_contrast.is_some() {
+            return 255;
+        }
+        let pulse = (t * 2.0).sin() * 0.15 + 0.85;
+        (pulse * 255.0) as u8
+    }
+
+    /// Returns button label text in the theme's button text color, if set
+    pub fn button_text(&self, text: &str, size: f32) -> egui::RichText {
+        let text = egui::RichText::new(text).size(size).strong();
+        match self.palette.button_text {
+            Some(color) => text.color(color),
+            None => text,
+        }
+    }
+}
+
+/// Relative luminance of a color as defined by WCAG 2
+fn luminance(color: Color32) -> f32 {
+    let channel = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(color.r()) + 0.7152 * channel(color.g()) + 0.0722 * channel(color.b())
+}
+
+/// WCAG 2 contrast ratio between two colors, from 1 to 21
+fn contrast_ratio(a: Color32, b: Color32) -> f32 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Returns `color`, lightened or darkened just enough to reach `ratio` against `background`.
+///
+/// Moves toward whichever of white or black contrasts more with the background,
+/// ending at that extreme if the ratio can't be reached sooner.
+fn with_contrast(color: Color32, background: Color32, ratio: f32) -> Color32 {
+    if contrast_ratio(color, background) >= ratio {
+        return color;
+    }
+
+    let white = contrast_ratio(Color32::WHITE, background);
+    let black = contrast_ratio(Color32::BLACK, background);
+    let target = if white >= black {
+        Color32::WHITE
+    } else {
+        Color32::BLACK
+    };
+
+    const STEPS: u8 = 20;
+    (1..=STEPS)
+        .map(|step| color.lerp_to_gamma(target, step as f32 / STEPS as f32))
+        .find(|c| contrast_ratio(*c, background) >= ratio)
+        .unwrap_or(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contrast_ratio_spans_one_to_twenty_one() {
+        assert!((contrast_ratio(Color32::BLACK, Color32::WHITE) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(Color32::WHITE, Color32::BLACK) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(Color32::GRAY, Color32::GRAY) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn with_contrast_keeps_readable_colors_and_raises_others() {
+        let background = hex("#1e1e1e");
+        assert_eq!(
+            with_contrast(Color32::WHITE, background, 7.0),
+            Color32::WHITE
+        );
+
+        let dim = hex("#404040");
+        let raised = with_contrast(dim, background, 7.0);
+        assert!(contrast_ratio(raised, background) >= 7.0);
+        assert!(raised.r() > dim.r(), "lightened on a dark background");
+
+        let raised = with_contrast(hex("#c0c0c0"), Color32::WHITE, 7.0);
+        assert!(contrast_ratio(raised, Color32::WHITE) >= 7.0);
+    }
+
+    #[test]
+    fn high_contrast_text_reaches_seven_to_one_in_every_preset() {
+        let accessibility = Accessibility {
+            high_contrast: true,
+            ..Default::default()
+        };
+        for preset in [
+            ThemePreset::Sse,
+            ThemePreset::Dark,
+            ThemePreset::HighContrast,
+        ] {
+            let config = ThemeConfig {
+                preset,
+                ..Default::default()
+            };
+            let theme = Theme::from_config(&config, &accessibility);
+            let palette = theme.palette;
+            let background = palette.background;
+            let button_text = palette.button_text.expect("button text is set");
+
+            let on_background = [
+                palette.primary,
+                palette.warning,
+                palette.danger,
+                palette.after_hours,
+                palette.muted,
+                theme.clock_color(background),
+                theme.readable(palette.success),
+                theme.readable(palette.info),
+                theme.readable(palette.music),
+            ];
+            for color in on_background {
+                let ratio = contrast_ratio(color, background);
+                assert!(ratio >= 7.0, "{preset:?}: {color:?} is {ratio}:1");
+            }
+            for fill in [palette.success, palette.info, palette.music] {
+                let ratio = contrast_ratio(button_text, fill);
+                assert!(ratio >= 7.0, "{preset:?}: button on {fill:?} is {ratio}:1");
+            }
+            let ratio = contrast_ratio(palette.toast_text, palette.warning);
+            assert!(ratio >= 7.0, "{preset:?}: toast text is {ratio}:1");
+        }
+    }
+}
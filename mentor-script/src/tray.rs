@@ -0,0 +1,205 @@
+//! System tray icon
+//!
+//! A StatusNotifierItem tray icon whose color shows the reminder state, with a
+//! menu of quick actions so the window can stay minimized. Linux only; on
+//! other platforms no icon is shown.
+
+use eframe::egui::Context;
+use std::sync::mpsc::{self, Receiver};
+
+/// Menu entry chosen in the tray
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayCommand {
+    Acknowledge,
+    Snooze,
+    PauseMusic,
+    OpenSongsFolder,
+    ToggleWindow,
+}
+
+/// What the tray icon shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayState {
+    /// Icon color as RGB
+    pub color: [u8; 3],
+    /// Tooltip text, e.g. "Half hour check in 3:12"
+    pub tooltip: String,
+    /// A reminder is Active, so acknowledge and snooze apply
+    pub reminder_active: bool,
+    /// Music is playing (`Some(false)`) or paused (`Some(true)`)
+    pub music_paused: Option<bool>,
+}
+
+/// Handle to the tray icon running on its own D-Bus thread
+pub struct TrayIcon {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    handle: Option<ksni::blocking::Handle<StatusTray>>,
+    state: Option<TrayState>,
+    commands: Receiver<TrayCommand>,
+}
+
+impl TrayIcon {
+    /// Starts the tray icon if `enabled`; failures are logged and leave no icon.
+    ///
+    /// `ctx` is woken up whenever a menu entry is chosen, so the command is
+    /// handled even while the window is hidden.
+    pub fn new(enabled: bool, ctx: &Context) -> Self {
+        let (sender, commands) = mpsc::channel();
+        #[cfg(not(all(unix, not(target_os = "macos"))))]
+        let _ = (enabled, sender, ctx);
+
+        Self {
+            #[cfg(all(unix, not(target_os = "macos")))]
+            handle: enabled
+                .then(|| StatusTray::spawn(sender, ctx.clone()))
+                .flatten(),
+            state: None,
+            commands,
+        }
+    }
+
+    /// Updates the icon, tooltip and menu when the state changed
+    pub fn set_state(&mut self, state: TrayState) {
+        if self.state.as_ref() == Some(&state) {
+            return;
+        }
+
+        #[cfg(all(unix, not(target_os = "macos")))]
+        if let Some(handle) = &self.handle {
+            let state = state.clone();
+            handle.update(|tray| tray.state = state);
+        }
+        self.state = Some(state);
+    }
+
+    /// Returns the next menu entry chosen, if any
+    pub fn poll(&self) -> Option<TrayCommand> {
+        self.commands.try_recv().ok()
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+struct StatusTray {
+    state: TrayState,
+    commands: mpsc::Sender<TrayCommand>,
+    ctx: Context,
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl StatusTray {
+    /// Side length of the generated icon in pixels
+    const ICON_SIZE: i32 = 32;
+
+    fn spawn(
+        commands: mpsc::Sender<TrayCommand>,
+        ctx: Context,
+    ) -> Option<ksni::blocking::Handle<Self>> {
+        use ksni::blocking::TrayMethods;
+
+        let tray = Self {
+            state: TrayState {
+                color: [128, 128, 128],
+                tooltip: "Mentor Script".to_string(),
+                reminder_active: false,
+                music_paused: None,
+            },
+            commands,
+            ctx,
+        };
+
+        tray.spawn()
+            .map_err(|e| tracing::warn!("Failed to start tray icon: {e}"))
+            .ok()
+    }
+
+    /// Menu item that sends `command` to the app
+    fn item(label: &str, enabled: bool, command: TrayCommand) -> ksni::MenuItem<Self> {
+        ksni::menu::StandardItem {
+            label: label.to_string(),
+            enabled,
+            activate: Box::new(move |tray: &mut Self| tray.send(command)),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    /// Sends `command` to the app and wakes it up to handle it
+    fn send(&self, command: TrayCommand) {
+        let _ = self.commands.send(command);
+        self.ctx.request_repaint();
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl ksni::Tray for StatusTray {
+    fn id(&self) -> String {
+        "mentor-script".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Mentor Script".to_string()
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        self.send(TrayCommand::ToggleWindow);
+    }
+
+    fn status(&self) -> ksni::Status {
+        if self.state.reminder_active {
+            ksni::Status::NeedsAttention
+        } else {
+            ksni::Status::Active
+        }
+    }
+
+    /// A filled circle in the state's color
+    fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+        let size = Self::ICON_SIZE;
+        let [r, g, b] = self.state.color;
+        let center = size as f32 / 2.0;
+
+        let data = (0..size * size)
+            .flat_map(|i| {
+                let x = (i % size) as f32 + 0.5 - center;
+                let y = (i / size) as f32 + 0.5 - center;
+                // One pixel of soft edge keeps the circle smooth.
+                let coverage = (center - (x * x + y * y).sqrt()).clamp(0.0, 1.0);
+                [(coverage * 255.0) as u8, r, g, b]
+            })
+            .collect();
+
+        vec![ksni::Icon {
+            width: size,
+            height: size,
+            data,
+        }]
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: self.state.tooltip.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        let active = self.state.reminder_active;
+        let pause_label = match self.state.music_paused {
+            Some(true) => "Resume music",
+            _ => "Pause music",
+        };
+
+        vec![
+            Self::item("Checked", active, TrayCommand::Acknowledge),
+            Self::item("Snooze", active, TrayCommand::Snooze),
+            Self::item(
+                pause_label,
+                self.state.music_paused.is_some(),
+                TrayCommand::PauseMusic,
+            ),
+            ksni::MenuItem::Separator,
+            Self::item("Open songs folder", true, TrayCommand::OpenSongsFolder),
+            Self::item("Show/hide window", true, TrayCommand::ToggleWindow),
+        ]
+    }
+}